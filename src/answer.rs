@@ -0,0 +1,83 @@
+use std::fmt::{self, Display, Formatter};
+
+/// The answer to one part of a puzzle.
+///
+/// Integers are normalised on construction so that the same value always
+/// compares equal regardless of the type the solver computed it in; only
+/// values above `i128::MAX` are stored as [`Answer::UInt`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    UInt(u128),
+    Text(String),
+    /// A rendered picture, one string per row.
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    pub fn grid<T: Display>(rows: impl IntoIterator<Item = T>) -> Self {
+        Answer::Grid(rows.into_iter().map(|row| row.to_string()).collect())
+    }
+
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Grid(_))
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::UInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(value: $int) -> Self {
+                    Answer::Int(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i8, i16, i32, i64, i128, u8, u16, u32, u64);
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Answer::Int(value as i128)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Int(value as i128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i128::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::UInt(value),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_owned())
+    }
+}
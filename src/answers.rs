@@ -0,0 +1,277 @@
+use crate::parse::{is_comment, parse_lines};
+use crate::{Part, PartReport};
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{read_to_string, write};
+use std::io::ErrorKind;
+use std::path::Path;
+
+pub const DEFAULT_ANSWERS_FILE: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/src/inputs/answers.txt");
+
+/// Known-correct answers, stored one per line as `<day> <part> <answer>`.
+///
+/// Multi-line answers are kept on one line with `\n`, `\r` and `\t` escaped.
+#[derive(Debug, Default)]
+pub struct Answers {
+    entries: BTreeMap<(u32, Part), String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", escape(expected)),
+            Verdict::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+fn escape(answer: &str) -> String {
+    answer
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = answer.chars();
+    while let Some(char) = chars.next() {
+        match (char, chars.clone().next()) {
+            ('\\', Some(escaped @ ('n' | 'r' | 't' | '\\'))) => {
+                unescaped.push(match escaped {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    _ => '\\',
+                });
+                chars.next();
+            }
+            (char, _) => unescaped.push(char),
+        }
+    }
+    unescaped
+}
+
+impl Answers {
+    /// Loads the answers file, treating a missing file as empty.
+    pub fn load(path: &Path) -> Result<Self> {
+        match read_to_string(path) {
+            Ok(text) => Self::parse(&text)
+                .with_context(|| format!("Could not parse answers {}.", path.display())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("Could not read answers {}.", path.display()))
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let entries = parse_lines(text, |line| {
+            if is_comment(line) {
+                return Ok(None);
+            }
+
+            let (day, rest) = line.split_once(" ")?;
+            let (part, answer) = rest.split_once(" ")?;
+
+            Ok(Some((
+                (day.parse()?, part.parse()?),
+                unescape(answer.as_str()),
+            )))
+        })?
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(Answers { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = self
+            .entries
+            .iter()
+            .map(|((day, part), answer)| format!("{} {} {}\n", day, part, escape(answer)))
+            .collect::<String>();
+
+        write(path, text).with_context(|| format!("Could not write answers {}.", path.display()))
+    }
+
+    pub fn verify(&self, report: &PartReport) -> Verdict {
+        match (self.entries.get(&(report.day, report.part)), &report.answer) {
+            (None, _) => Verdict::Unknown,
+            (Some(expected), Ok(answer)) if *expected == answer.to_string() => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+
+    /// Records the answer of `report` as correct, returning false if the part
+    /// errored and there was nothing to record.
+    pub fn record(&mut self, report: &PartReport) -> bool {
+        match &report.answer {
+            Ok(answer) => {
+                self.entries
+                    .insert((report.day, report.part), answer.to_string());
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, unescape, Answers, Verdict};
+    use crate::{Answer, Part, PartReport, PuzzleError};
+
+    use std::fs::remove_file;
+    use std::path::PathBuf;
+    use std::process;
+    use std::time::Duration;
+
+    fn report(day: u32, part: Part, answer: Result<Answer, PuzzleError>) -> PartReport {
+        PartReport {
+            day,
+            part,
+            answer,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+        }
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("my-project-{}-{}", process::id(), name))
+    }
+
+    #[test]
+    fn escapes_round_trip() {
+        for answer in [
+            "plain",
+            "two\nlines",
+            "tab\tseparated",
+            "carriage\r\nreturn",
+            r"back\slash",
+            r"literal \n is not a newline",
+            "trailing\\",
+            "\\\n\t\\t",
+        ] {
+            let escaped = escape(answer);
+            assert!(!escaped.contains(['\n', '\r', '\t']), "{:?}", escaped);
+            assert_eq!(unescape(&escaped), answer);
+        }
+    }
+
+    #[test]
+    fn leaves_unknown_escapes_alone() {
+        assert_eq!(unescape(r"a\qb\"), r"a\qb\");
+    }
+
+    #[test]
+    fn saves_and_loads_answers() {
+        let mut answers = Answers::default();
+        let reports = [
+            report(1, Part::One, Ok(Answer::UInt(142))),
+            report(1, Part::Two, Ok(Answer::Int(-3))),
+            report(2, Part::One, Ok(Answer::Text("a\tb\\c d".to_string()))),
+            report(2, Part::Two, Ok(Answer::grid(["#..\\", "\t.#.", "..# "]))),
+        ];
+        for report in &reports {
+            assert!(answers.record(report));
+        }
+
+        let path = temp_file("answers.txt");
+        answers.save(&path).unwrap();
+        let loaded = Answers::load(&path).unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(loaded.entries, answers.entries);
+        for report in &reports {
+            assert_eq!(loaded.verify(report), Verdict::Pass);
+        }
+    }
+
+    #[test]
+    fn loads_missing_file_as_empty() {
+        let answers = Answers::load(&temp_file("missing-answers.txt")).unwrap();
+        assert!(answers.entries.is_empty());
+    }
+
+    #[test]
+    fn parses_comments_and_blank_lines() {
+        let answers = Answers::parse("# day part answer\n\n3 1 4361\n3 2 two words\n").unwrap();
+        assert_eq!(answers.entries.len(), 2);
+        assert_eq!(answers.entries[&(3, Part::Two)], "two words");
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for (text, line) in [
+            ("1 1 142\n1\n", 2),
+            ("1 1\n", 1),
+            ("x 1 142\n", 1),
+            ("1 1 142\n\n1 3 281\n", 3),
+        ] {
+            let err = Answers::parse(text).unwrap_err();
+            let err: &PuzzleError = err.downcast_ref().unwrap();
+            assert_eq!(err.line, Some(line), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn verifies_reports() {
+        let answers = Answers::parse("1 1 142\n1 2 grid\\nrows\n").unwrap();
+
+        assert_eq!(
+            answers.verify(&report(1, Part::One, Ok(Answer::UInt(142)))),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(&report(1, Part::Two, Ok(Answer::grid(["grid", "rows"])))),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(&report(1, Part::One, Ok(Answer::UInt(143)))),
+            Verdict::Fail {
+                expected: "142".to_string()
+            }
+        );
+        assert_eq!(
+            answers.verify(&report(
+                1,
+                Part::One,
+                Err(PuzzleError::no_solution("No answer."))
+            )),
+            Verdict::Fail {
+                expected: "142".to_string()
+            }
+        );
+        assert_eq!(
+            answers.verify(&report(2, Part::One, Ok(Answer::UInt(142)))),
+            Verdict::Unknown
+        );
+    }
+
+    #[test]
+    fn does_not_record_errors() {
+        let mut answers = Answers::default();
+        assert!(!answers.record(&report(
+            1,
+            Part::One,
+            Err(PuzzleError::no_solution("No answer."))
+        )));
+        assert!(answers.entries.is_empty());
+    }
+}
@@ -0,0 +1,470 @@
+use crate::parse::{is_comment, parse_lines, Span};
+use crate::report::render_table;
+use crate::{Part, PuzzleError, Solver, Tracer};
+
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Summary statistics over the timings of repeated runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let min = *sorted.first()?;
+        let median = (sorted[(sorted.len() - 1) / 2] + sorted[sorted.len() / 2]) / 2;
+
+        let secs = sorted
+            .iter()
+            .map(Duration::as_secs_f64)
+            .collect::<Vec<f64>>();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / secs.len() as f64;
+
+        Some(Stats {
+            min,
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// The timings of one part over every measured run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchResult {
+    pub day: u32,
+    pub part: Part,
+    pub runs: usize,
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Runs `part` `warmup` times untimed and then `runs` times timed.
+///
+/// Fails on the first run that does not produce an answer, since timing a
+/// failure is meaningless.
+pub fn bench(
+    solver: &dyn Solver,
+    part: Part,
+    input: &str,
+    warmup: usize,
+    runs: usize,
+) -> Result<BenchResult, PuzzleError> {
+    let tracer = Tracer::default();
+
+    for _ in 0..warmup {
+        solver.run_part(part, input, &tracer).answer?;
+    }
+
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut solve_times = Vec::with_capacity(runs);
+    for _ in 0..runs.max(1) {
+        let report = solver.run_part(part, input, &tracer);
+        report.answer?;
+        parse_times.push(report.parse_time);
+        solve_times.push(report.solve_time);
+    }
+
+    let stats = |samples: &[Duration]| {
+        Stats::from_samples(samples).ok_or_else(|| PuzzleError::invariant("No runs were timed."))
+    };
+
+    Ok(BenchResult {
+        day: solver.day(),
+        part,
+        runs: parse_times.len(),
+        parse: stats(&parse_times)?,
+        solve: stats(&solve_times)?,
+    })
+}
+
+/// Saved results of a previous benchmark, keyed by day and part.
+///
+/// Stored one part per line as `<day> <part> <runs>` followed by the min,
+/// median, mean and stddev of parsing and then solving, in nanoseconds.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    results: BTreeMap<(u32, Part), BenchResult>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = read_to_string(path)
+            .with_context(|| format!("Could not read benchmark {}.", path.display()))?;
+
+        Self::parse(&text).with_context(|| format!("Could not parse benchmark {}.", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let results = parse_lines(text, |line| {
+            if is_comment(line) {
+                return Ok(None);
+            }
+
+            let fields = line.words().collect::<Vec<Span>>();
+            let [day, part, runs, timings @ ..] = fields.as_slice() else {
+                bail!(line.error("Expected a day, part and run count."));
+            };
+
+            let timings = timings
+                .iter()
+                .map(|nanos| Ok(Duration::from_nanos(nanos.parse()?)))
+                .collect::<Result<Vec<Duration>>>()?;
+            let [parse_min, parse_median, parse_mean, parse_stddev, solve_min, solve_median, solve_mean, solve_stddev] =
+                timings.as_slice()
+            else {
+                bail!(line.error(format!("Expected 8 timings, found {}.", timings.len())));
+            };
+
+            let result = BenchResult {
+                day: day.parse()?,
+                part: part.parse()?,
+                runs: runs.parse()?,
+                parse: Stats {
+                    min: *parse_min,
+                    median: *parse_median,
+                    mean: *parse_mean,
+                    stddev: *parse_stddev,
+                },
+                solve: Stats {
+                    min: *solve_min,
+                    median: *solve_median,
+                    mean: *solve_mean,
+                    stddev: *solve_stddev,
+                },
+            };
+
+            Ok(Some(((result.day, result.part), result)))
+        })?
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(Baseline { results })
+    }
+
+    /// Saves `results`, keeping any saved parts that were not benchmarked.
+    pub fn save(path: &Path, results: &[BenchResult]) -> Result<()> {
+        let mut baseline = match path.exists() {
+            true => Self::load(path)?,
+            false => Self::default(),
+        };
+        results.iter().for_each(|result| {
+            baseline.results.insert((result.day, result.part), *result);
+        });
+
+        let nanos = |stats: &Stats| {
+            [stats.min, stats.median, stats.mean, stats.stddev]
+                .map(|duration| duration.as_nanos().to_string())
+                .join(" ")
+        };
+        let text = baseline
+            .results
+            .values()
+            .map(|result| {
+                format!(
+                    "{} {} {} {} {}\n",
+                    result.day,
+                    result.part,
+                    result.runs,
+                    nanos(&result.parse),
+                    nanos(&result.solve)
+                )
+            })
+            .collect::<String>();
+
+        write(
+            path,
+            format!("# day part runs parse(min median mean stddev) solve(min median mean stddev), ns\n{}", text),
+        )
+        .with_context(|| format!("Could not write benchmark {}.", path.display()))
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&BenchResult> {
+        self.results.get(&(day, part))
+    }
+}
+
+/// The relative change in median from `baseline` to `current`, in percent.
+fn change(baseline: Duration, current: Duration) -> Option<f64> {
+    let baseline = baseline.as_secs_f64();
+    (baseline > 0.0).then(|| (current.as_secs_f64() - baseline) / baseline * 100.0)
+}
+
+/// How many standard deviations a median has to move by to count as slower.
+const NOISE_STDDEVS: f64 = 3.0;
+
+/// How much slower a step has to get to count as a regression.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    /// Percent slower than the baseline median.
+    pub threshold: f64,
+    /// Slowdowns no larger than this are treated as timer and scheduler
+    /// noise, however large they are relative to a step of a few
+    /// microseconds.
+    pub noise_floor: Duration,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance {
+            threshold: 10.0,
+            noise_floor: Duration::from_micros(200),
+        }
+    }
+}
+
+/// Whether a step got more than `tolerance.threshold` percent slower than
+/// the baseline, by more than both the noise floor and the spread of the
+/// runs.
+fn is_slower(baseline: &Stats, current: &Stats, tolerance: &Tolerance) -> bool {
+    let spread = baseline
+        .stddev
+        .as_secs_f64()
+        .hypot(current.stddev.as_secs_f64());
+    let margin = tolerance
+        .noise_floor
+        .max(Duration::from_secs_f64(spread * NOISE_STDDEVS));
+
+    change(baseline.median, current.median).is_some_and(|change| change > tolerance.threshold)
+        && current.median.saturating_sub(baseline.median) > margin
+}
+
+/// Whether any step of `result` got meaningfully slower than the baseline.
+pub fn is_regression(result: &BenchResult, baseline: &BenchResult, tolerance: &Tolerance) -> bool {
+    is_slower(&baseline.parse, &result.parse, tolerance)
+        || is_slower(&baseline.solve, &result.solve, tolerance)
+}
+
+/// Renders a table of every result, with the change in median against
+/// `baseline` when one is given.
+pub fn bench_table(
+    results: &[BenchResult],
+    baseline: Option<&Baseline>,
+    tolerance: &Tolerance,
+) -> String {
+    let mut headers = [
+        "Day", "Part", "Step", "Runs", "Min", "Median", "Mean", "Stddev",
+    ]
+    .map(String::from)
+    .to_vec();
+    if baseline.is_some() {
+        headers.extend(["Baseline", "Change", ""].map(String::from));
+    }
+
+    let rows = results
+        .iter()
+        .flat_map(|result| {
+            let previous = baseline.and_then(|baseline| baseline.get(result.day, result.part));
+
+            [
+                (
+                    "parse",
+                    result.parse,
+                    previous.map(|previous| previous.parse),
+                ),
+                (
+                    "solve",
+                    result.solve,
+                    previous.map(|previous| previous.solve),
+                ),
+            ]
+            .map(|(step, stats, previous)| {
+                let mut row = vec![
+                    result.day.to_string(),
+                    result.part.to_string(),
+                    String::from(step),
+                    result.runs.to_string(),
+                    format!("{:.2?}", stats.min),
+                    format!("{:.2?}", stats.median),
+                    format!("{:.2?}", stats.mean),
+                    format!("{:.2?}", stats.stddev),
+                ];
+
+                if baseline.is_some() {
+                    let change =
+                        previous.and_then(|previous| change(previous.median, stats.median));
+                    row.extend([
+                        previous.map_or(String::from("-"), |previous| {
+                            format!("{:.2?}", previous.median)
+                        }),
+                        change.map_or(String::from("-"), |change| format!("{:+.1}%", change)),
+                        match previous {
+                            Some(previous) if is_slower(&previous, &stats, tolerance) => {
+                                String::from("REGRESSION")
+                            }
+                            _ => String::new(),
+                        },
+                    ]);
+                }
+
+                row
+            })
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    render_table(&headers, &rows, None, &[2, 10])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_regression, is_slower, Baseline, BenchResult, Stats, Tolerance};
+    use crate::Part;
+
+    use std::fs::{remove_file, write};
+    use std::path::PathBuf;
+    use std::process;
+    use std::time::Duration;
+
+    fn micros(micros: u64) -> Duration {
+        Duration::from_micros(micros)
+    }
+
+    /// Stats with the given median and stddev, in microseconds.
+    fn stats(median: u64, stddev: u64) -> Stats {
+        Stats {
+            min: micros(median),
+            median: micros(median),
+            mean: micros(median),
+            stddev: micros(stddev),
+        }
+    }
+
+    fn result(day: u32, part: Part, parse: Stats, solve: Stats) -> BenchResult {
+        BenchResult {
+            day,
+            part,
+            runs: 10,
+            parse,
+            solve,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("my-project-{}-{}", process::id(), name))
+    }
+
+    #[test]
+    fn stats_from_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+
+        let odd = Stats::from_samples(&[micros(30), micros(10), micros(20)]).unwrap();
+        assert_eq!(
+            (odd.min, odd.median, odd.mean),
+            (micros(10), micros(20), micros(20))
+        );
+
+        let even = Stats::from_samples(&[micros(4), micros(1), micros(2), micros(9)]).unwrap();
+        assert_eq!(
+            (even.min, even.median, even.mean),
+            (micros(1), micros(3), micros(4))
+        );
+        // Deviations of 0, -3, -2 and 5 from the mean give a variance of 38 / 4.
+        let expected = (38.0f64 / 4.0).sqrt() * 1e-6;
+        assert!((even.stddev.as_secs_f64() - expected).abs() < 1e-9);
+
+        let same = Stats::from_samples(&[micros(7); 5]).unwrap();
+        assert_eq!(same.stddev, Duration::ZERO);
+    }
+
+    #[test]
+    fn slower_needs_threshold_floor_and_spread() {
+        let tolerance = Tolerance::default();
+
+        // 50% and 5ms slower.
+        assert!(is_slower(&stats(10_000, 0), &stats(15_000, 0), &tolerance));
+        // 5% slower is within the threshold.
+        assert!(!is_slower(&stats(10_000, 0), &stats(10_500, 0), &tolerance));
+        // Faster is never slower.
+        assert!(!is_slower(&stats(10_000, 0), &stats(5_000, 0), &tolerance));
+        // 5ms slower, but within three standard deviations.
+        assert!(!is_slower(
+            &stats(10_000, 2_000),
+            &stats(15_000, 1_000),
+            &tolerance
+        ));
+        // Three times slower, but only by 20µs.
+        assert!(!is_slower(&stats(10, 0), &stats(30, 0), &tolerance));
+    }
+
+    #[test]
+    fn noise_floor_is_configurable() {
+        let tolerance = Tolerance {
+            threshold: 10.0,
+            noise_floor: Duration::ZERO,
+        };
+
+        assert!(is_slower(&stats(10, 0), &stats(30, 0), &tolerance));
+        assert!(!is_slower(&stats(10, 0), &stats(10, 0), &tolerance));
+        assert!(!is_slower(&stats(10, 1), &stats(12, 1), &tolerance));
+    }
+
+    #[test]
+    fn regression_in_either_step() {
+        let tolerance = Tolerance::default();
+        let baseline = result(1, Part::One, stats(1_000, 0), stats(10_000, 0));
+
+        let slower_solve = result(1, Part::One, stats(1_000, 0), stats(20_000, 0));
+        let slower_parse = result(1, Part::One, stats(5_000, 0), stats(10_000, 0));
+        let unchanged = result(1, Part::One, stats(1_050, 0), stats(9_000, 0));
+
+        assert!(is_regression(&slower_solve, &baseline, &tolerance));
+        assert!(is_regression(&slower_parse, &baseline, &tolerance));
+        assert!(!is_regression(&unchanged, &baseline, &tolerance));
+    }
+
+    #[test]
+    fn baseline_round_trips_and_keeps_other_parts() {
+        let path = temp_path("baseline-round-trip.txt");
+        let _ = remove_file(&path);
+
+        let first = result(3, Part::One, stats(12, 1), stats(3_456, 78));
+        let second = result(3, Part::Two, stats(12, 1), stats(9_999, 0));
+        Baseline::save(&path, &[first, second]).unwrap();
+
+        let replaced = result(3, Part::Two, stats(13, 2), stats(8_888, 5));
+        let added = result(11, Part::One, stats(1, 0), stats(2, 0));
+        Baseline::save(&path, &[replaced, added]).unwrap();
+
+        let baseline = Baseline::load(&path).unwrap();
+        remove_file(&path).unwrap();
+
+        assert_eq!(baseline.get(3, Part::One), Some(&first));
+        assert_eq!(baseline.get(3, Part::Two), Some(&replaced));
+        assert_eq!(baseline.get(11, Part::One), Some(&added));
+        assert_eq!(baseline.get(11, Part::Two), None);
+    }
+
+    #[test]
+    fn rejects_malformed_baselines() {
+        assert!(Baseline::parse("# only a comment\n\n")
+            .unwrap()
+            .results
+            .is_empty());
+        assert!(Baseline::parse("1 1 10 1 2 3 4 5 6 7\n").is_err());
+        assert!(Baseline::parse("1 3 10 1 2 3 4 5 6 7 8\n").is_err());
+        assert!(Baseline::parse("1 1\n").is_err());
+
+        let path = temp_path("baseline-malformed.txt");
+        write(&path, "1 1 10 1 2 3 4 5 6 7 x\n").unwrap();
+        let err = Baseline::load(&path).unwrap_err();
+        remove_file(&path).unwrap();
+        assert!(format!("{:#}", err).contains("Could not parse benchmark"));
+    }
+}
@@ -0,0 +1,541 @@
+use my_project::answers::DEFAULT_ANSWERS_FILE;
+use my_project::bench::Tolerance;
+use my_project::input::InputSource;
+use my_project::report::Format;
+use my_project::trace::Level;
+use my_project::{Part, RunMode};
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+// Paths are resolved from where the crate was built, not the working
+// directory, so the defaults below are spelled out in full.
+pub const USAGE: &str = concat!(
+    "\
+Usage:
+    my-project [run] [DAYS...] [--part <1|2>] [--inputs <DIR> | --input <FILE>]
+                    [--verify | --record] [--answers <FILE>]
+                    [--trace <LEVEL>] [--trace-days <DAYS>] [--trace-file <FILE>]
+                    [--jobs <N>] [--format <text|json|jsonl>]
+    my-project bench [DAYS...] [--part <1|2>] [--inputs <DIR> | --input <FILE>]
+                    [--runs <N>] [--warmup <N>] [--save <FILE>]
+                    [--compare <FILE>] [--threshold <PERCENT>]
+                    [--noise-floor <MICROS>]
+    my-project new-day <DAY>
+    my-project help
+
+Days:
+    all          Every implemented day (the default)
+    7            A single day
+    3..9         Days 3 through 8
+    3..=9        Days 3 through 9
+    1,4,7        A comma separated list of any of the above
+
+Options:
+    -p, --part <1|2>  Only run the given part
+    --inputs <DIR>    Read day{N}.txt inputs from DIR
+                      (default: ",
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/inputs)
+    -i, --input <FILE>
+                      Read the input of a single day from FILE, or stdin if FILE is -
+    --verify          Check answers against the answers file, failing on a mismatch
+    --record          Save the answers as the new known-correct answers
+    --answers <FILE>  The answers file to verify against or record to
+                      (default: ",
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/inputs/answers.txt)
+    -f, --format <text|json|jsonl>
+                      Print reports as text, one JSON document, or JSON lines as
+                      each part finishes (default: text)
+    -j, --jobs <N>    Run up to N parts at once (default: the number of CPUs)
+    -t, --trace <summary|step|verbose>
+                      Trace what the solvers do, each level including the previous
+    -d, --debug       Shorthand for --trace verbose
+    --trace-days <DAYS>
+                      Only trace the given days, in the same format as DAYS
+    --trace-file <FILE>
+                      Write the trace to FILE instead of stdout, or stderr
+                      with --format json or jsonl
+    -h, --help        Print this message
+
+Bench options:
+    --runs <N>        Time each part N times (default: 10)
+    --warmup <N>      Run each part N times untimed first (default: 2)
+    --save <FILE>     Save the results to FILE, keeping other saved parts
+    --compare <FILE>  Compare medians against results saved in FILE
+    --threshold <PERCENT>
+                      Fail when a median is this much slower than the
+                      compared results, and slower by more than the noise
+                      floor and three standard deviations (default: 10)
+    --noise-floor <MICROS>
+                      Never count a slowdown of up to this many microseconds
+                      as a regression, use 0 to compare parts that only take
+                      a few microseconds (default: 200)
+
+New day:
+    Generates src/days/day{DAY}.rs from a template with an example test stub,
+    registers it in src/days/mod.rs and creates an empty src/inputs/day{DAY}.txt,
+    all under ",
+    env!("CARGO_MANIFEST_DIR"),
+    ".
+    Refuses to overwrite a day that already exists."
+);
+
+#[derive(Debug, PartialEq)]
+pub enum DaySelection {
+    All,
+    Days(Vec<u32>),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AnswersMode {
+    Ignore,
+    Verify,
+    Record,
+}
+
+pub struct RunOptions {
+    pub days: DaySelection,
+    pub run_mode: RunMode,
+    pub input: InputSource,
+    pub answers_mode: AnswersMode,
+    pub answers_file: PathBuf,
+    pub trace_level: Option<Level>,
+    pub trace_days: Option<Vec<u32>>,
+    pub trace_file: Option<PathBuf>,
+    pub jobs: usize,
+    pub format: Format,
+}
+
+pub struct BenchOptions {
+    pub days: DaySelection,
+    pub run_mode: RunMode,
+    pub input: InputSource,
+    pub runs: usize,
+    pub warmup: usize,
+    pub save: Option<PathBuf>,
+    pub compare: Option<PathBuf>,
+    pub tolerance: Tolerance,
+}
+
+pub enum Command {
+    Run(RunOptions),
+    Bench(BenchOptions),
+    NewDay(u32),
+    Help,
+}
+
+/// Advent of Code runs from the 1st to the 25th of December.
+const DAYS: RangeInclusive<u32> = 1..=25;
+
+fn parse_day(day: &str) -> Result<u32> {
+    let day = day
+        .parse()
+        .with_context(|| format!("Invalid day \"{}\".", day))?;
+
+    if !DAYS.contains(&day) {
+        bail!(
+            "Day {} is out of range, expected {} to {}.",
+            day,
+            DAYS.start(),
+            DAYS.end()
+        );
+    }
+
+    Ok(day)
+}
+
+/// Adds `new` to `days`, skipping any already selected.
+fn extend_days(days: &mut Vec<u32>, new: Vec<u32>) {
+    for day in new {
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+}
+
+fn parse_days(selection: &str) -> Result<Vec<u32>> {
+    let days = selection
+        .split_terminator(',')
+        .try_fold(Vec::new(), |mut days, token| {
+            let range = if let Some((start, end)) = token.split_once("..=") {
+                (parse_day(start)?..=parse_day(end)?).collect()
+            } else if let Some((start, end)) = token.split_once("..") {
+                // The end is excluded, so it may be one past the last day.
+                let end = match end.parse() {
+                    Ok(end) if end == DAYS.end() + 1 => end,
+                    _ => parse_day(end)?,
+                };
+                (parse_day(start)?..end).collect()
+            } else {
+                vec![parse_day(token)?]
+            };
+
+            if range.is_empty() {
+                bail!("Day range \"{}\" is empty.", token);
+            }
+            extend_days(&mut days, range);
+
+            Ok(days)
+        })?;
+
+    if days.is_empty() {
+        bail!("No days selected in \"{}\".", selection);
+    }
+
+    Ok(days)
+}
+
+fn parse_part(part: Option<String>) -> Result<Part> {
+    let part = part.context("--part requires a value.")?;
+    part.parse()
+        .map_err(|err| anyhow!("Invalid part \"{}\", {}.", part, err))
+}
+
+fn parse_number<T: FromStr>(value: Option<String>, option: &str) -> Result<T> {
+    value
+        .ok_or_else(|| anyhow!("{} requires a value.", option))?
+        .parse()
+        .map_err(|_| anyhow!("{} must be a number.", option))
+}
+
+fn parse_path(path: Option<String>, option: &str) -> Result<PathBuf> {
+    path.map(PathBuf::from)
+        .ok_or_else(|| anyhow!("{} requires a value.", option))
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command> {
+    let mut args = args.into_iter().peekable();
+
+    let bench = match args.peek().map(String::as_str) {
+        Some("help" | "-h" | "--help") => return Ok(Command::Help),
+        Some("run") => {
+            args.next();
+            false
+        }
+        Some("bench") => {
+            args.next();
+            true
+        }
+        Some("new-day") => {
+            args.next();
+            let day = parse_day(&args.next().context("new-day requires a day.")?)?;
+            if let Some(arg) = args.next() {
+                bail!("Unexpected argument \"{}\" after new-day {}.", arg, day);
+            }
+            return Ok(Command::NewDay(day));
+        }
+        _ => false,
+    };
+
+    let mut all = false;
+    let mut days: Option<Vec<u32>> = None;
+    let mut part = None;
+    let mut trace_level = None;
+    let mut trace_days = None;
+    let mut trace_file = None;
+    let mut format = Format::Text;
+    let mut jobs = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let mut input = InputSource::default();
+    let mut answers_mode = AnswersMode::Ignore;
+    let mut answers_file = PathBuf::from(DEFAULT_ANSWERS_FILE);
+    let mut runs = 10;
+    let mut warmup = 2;
+    let mut save = None;
+    let mut compare = None;
+    let mut tolerance = Tolerance::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--runs" | "--warmup" | "--save" | "--compare" | "--threshold" | "--noise-floor"
+                if !bench =>
+            {
+                bail!("{} can only be used with bench.", arg)
+            }
+            "-f" | "--format" | "-j" | "--jobs" | "-t" | "--trace" | "-d" | "--debug"
+            | "--trace-days" | "--trace-file" | "--verify" | "--record" | "--answers"
+                if bench =>
+            {
+                bail!("{} can not be used with bench.", arg)
+            }
+            "--runs" => {
+                runs = match parse_number(args.next(), &arg)? {
+                    0 => bail!("{} must be at least 1.", arg),
+                    runs => runs,
+                }
+            }
+            "--warmup" => warmup = parse_number(args.next(), &arg)?,
+            "--save" => save = Some(parse_path(args.next(), &arg)?),
+            "--compare" => compare = Some(parse_path(args.next(), &arg)?),
+            "--threshold" => tolerance.threshold = parse_number(args.next(), &arg)?,
+            "--noise-floor" => {
+                tolerance.noise_floor = Duration::from_micros(parse_number(args.next(), &arg)?)
+            }
+            "-p" | "--part" => part = Some(parse_part(args.next())?),
+            "-f" | "--format" => {
+                format = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} requires a value.", arg))?
+                    .parse()?;
+            }
+            "-j" | "--jobs" => {
+                jobs = match parse_number(args.next(), &arg)? {
+                    0 => bail!("{} must be at least 1.", arg),
+                    jobs => jobs,
+                }
+            }
+            "-t" | "--trace" => {
+                let level = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} requires a value.", arg))?;
+                trace_level = Some(level.parse()?);
+            }
+            "-d" | "--debug" => trace_level = Some(Level::Verbose),
+            "--trace-days" => {
+                let days = args
+                    .next()
+                    .ok_or_else(|| anyhow!("{} requires a value.", arg))?;
+                trace_days = Some(parse_days(&days)?);
+            }
+            "--trace-file" => trace_file = Some(parse_path(args.next(), &arg)?),
+            "--inputs" => input = InputSource::Directory(parse_path(args.next(), &arg)?),
+            "-i" | "--input" => {
+                input = match parse_path(args.next(), &arg)? {
+                    path if path.as_os_str() == "-" => InputSource::Stdin,
+                    path => InputSource::File(path),
+                }
+            }
+            "--verify" | "--record" if answers_mode != AnswersMode::Ignore => {
+                bail!("--verify and --record can not be combined.")
+            }
+            "--verify" => answers_mode = AnswersMode::Verify,
+            "--record" => answers_mode = AnswersMode::Record,
+            "--answers" => answers_file = parse_path(args.next(), &arg)?,
+            "all" => all = true,
+            arg if arg.starts_with('-') => bail!("Unknown option \"{}\".", arg),
+            arg => extend_days(days.get_or_insert_with(Vec::new), parse_days(arg)?),
+        }
+    }
+
+    let run_mode = match part {
+        None => RunMode::Result,
+        Some(Part::One) => RunMode::ResultPartOne,
+        Some(Part::Two) => RunMode::ResultPartTwo,
+    };
+
+    if trace_level.is_none() && (trace_days.is_some() || trace_file.is_some()) {
+        bail!("--trace-days and --trace-file require --trace.");
+    }
+
+    let days = match days {
+        Some(days) if !all => DaySelection::Days(days),
+        _ => DaySelection::All,
+    };
+
+    if input.is_single_day() && !matches!(&days, DaySelection::Days(days) if days.len() == 1) {
+        bail!("--input can only be used with a single day.");
+    }
+
+    if bench {
+        return Ok(Command::Bench(BenchOptions {
+            days,
+            run_mode,
+            input,
+            runs,
+            warmup,
+            save,
+            compare,
+            tolerance,
+        }));
+    }
+
+    Ok(Command::Run(RunOptions {
+        days,
+        run_mode,
+        input,
+        answers_mode,
+        answers_file,
+        trace_level,
+        trace_days,
+        trace_file,
+        jobs,
+        format,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, parse_days, AnswersMode, Command, DaySelection, RunOptions};
+    use my_project::input::InputSource;
+    use my_project::report::Format;
+    use my_project::trace::Level;
+    use my_project::RunMode;
+
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn parse(line: &str) -> anyhow::Result<Command> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    fn run(line: &str) -> RunOptions {
+        match parse(line) {
+            Ok(Command::Run(options)) => options,
+            Ok(_) => panic!("\"{}\" is not a run", line),
+            Err(err) => panic!("\"{}\" failed: {}", line, err),
+        }
+    }
+
+    fn error(line: &str) -> String {
+        match parse(line) {
+            Ok(_) => panic!("\"{}\" parsed", line),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn days_error(selection: &str) -> String {
+        parse_days(selection).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_day_selections() {
+        assert_eq!(parse_days("7").unwrap(), [7]);
+        assert_eq!(parse_days("3..6").unwrap(), [3, 4, 5]);
+        assert_eq!(parse_days("3..=6").unwrap(), [3, 4, 5, 6]);
+        assert_eq!(parse_days("1,4..6,9").unwrap(), [1, 4, 5, 9]);
+        assert_eq!(parse_days("24..26").unwrap(), [24, 25]);
+        assert_eq!(parse_days("25,").unwrap(), [25]);
+    }
+
+    #[test]
+    fn drops_duplicate_days() {
+        assert_eq!(parse_days("3,3").unwrap(), [3]);
+        assert_eq!(parse_days("4,2..=5,1").unwrap(), [4, 2, 3, 5, 1]);
+
+        let options = run("5 1..=5 5");
+        assert_eq!(options.days, DaySelection::Days(vec![5, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn rejects_bad_day_selections() {
+        assert_eq!(days_error("5..5"), "Day range \"5..5\" is empty.");
+        assert_eq!(days_error("6..3"), "Day range \"6..3\" is empty.");
+        assert_eq!(days_error("6..=3"), "Day range \"6..=3\" is empty.");
+        assert_eq!(days_error(""), "No days selected in \"\".");
+        assert_eq!(days_error("x"), "Invalid day \"x\".");
+        assert_eq!(days_error("1,,2"), "Invalid day \"\".");
+        assert_eq!(days_error("0"), "Day 0 is out of range, expected 1 to 25.");
+        assert_eq!(
+            days_error("26"),
+            "Day 26 is out of range, expected 1 to 25."
+        );
+        assert_eq!(
+            days_error("20..=26"),
+            "Day 26 is out of range, expected 1 to 25."
+        );
+        assert_eq!(
+            days_error("20..27"),
+            "Day 27 is out of range, expected 1 to 25."
+        );
+    }
+
+    #[test]
+    fn parses_run_options() {
+        let options = run("");
+        assert_eq!(options.days, DaySelection::All);
+        assert!(options.run_mode == RunMode::Result);
+        assert_eq!(options.input, InputSource::default());
+        assert_eq!(options.answers_mode, AnswersMode::Ignore);
+        assert_eq!(options.format, Format::Text);
+        assert_eq!(options.trace_level, None);
+
+        let options = run("run 3,5 -p 2 -f jsonl -j 4 -t step --trace-days 3 --verify");
+        assert_eq!(options.days, DaySelection::Days(vec![3, 5]));
+        assert!(options.run_mode == RunMode::ResultPartTwo);
+        assert_eq!(options.format, Format::JsonLines);
+        assert_eq!(options.jobs, 4);
+        assert_eq!(options.trace_level, Some(Level::Step));
+        assert_eq!(options.trace_days, Some(vec![3]));
+        assert_eq!(options.answers_mode, AnswersMode::Verify);
+
+        let options = run("7 --part 1 -i - -d --record --answers answers.txt");
+        assert!(options.run_mode == RunMode::ResultPartOne);
+        assert_eq!(options.input, InputSource::Stdin);
+        assert_eq!(options.trace_level, Some(Level::Verbose));
+        assert_eq!(options.answers_mode, AnswersMode::Record);
+        assert_eq!(options.answers_file, PathBuf::from("answers.txt"));
+
+        assert_eq!(run("3 all").days, DaySelection::All);
+        assert_eq!(
+            run("--inputs elsewhere").input,
+            InputSource::Directory(PathBuf::from("elsewhere"))
+        );
+    }
+
+    #[test]
+    fn parses_other_commands() {
+        assert!(matches!(parse("help"), Ok(Command::Help)));
+        assert!(matches!(parse("3 -p 2 --help"), Ok(Command::Help)));
+        assert!(matches!(parse("new-day 12"), Ok(Command::NewDay(12))));
+
+        let Ok(Command::Bench(options)) =
+            parse("bench 2..4 --runs 5 --warmup 0 --threshold 5 --noise-floor 0")
+        else {
+            panic!("not a bench");
+        };
+        assert_eq!(options.days, DaySelection::Days(vec![2, 3]));
+        assert_eq!((options.runs, options.warmup), (5, 0));
+        assert_eq!(options.tolerance.threshold, 5.0);
+        assert_eq!(options.tolerance.noise_floor, Duration::ZERO);
+    }
+
+    #[test]
+    fn rejects_bad_flags() {
+        assert_eq!(error("--bogus"), "Unknown option \"--bogus\".");
+        assert_eq!(error("--part"), "--part requires a value.");
+        assert_eq!(error("-p 3"), "Invalid part \"3\", expected 1 or 2.");
+        assert_eq!(error("--jobs 0"), "--jobs must be at least 1.");
+        assert_eq!(error("-j many"), "-j must be a number.");
+        assert_eq!(error("--format"), "--format requires a value.");
+        assert!(error("-f xml").starts_with("Invalid format \"xml\""));
+        assert!(error("-t loud").starts_with("Invalid trace level \"loud\""));
+        assert_eq!(
+            error("--verify --record"),
+            "--verify and --record can not be combined."
+        );
+        assert_eq!(
+            error("--trace-file trace.txt"),
+            "--trace-days and --trace-file require --trace."
+        );
+        assert_eq!(
+            error("1 2 -i input.txt"),
+            "--input can only be used with a single day."
+        );
+        assert_eq!(error("--runs 3"), "--runs can only be used with bench.");
+        assert_eq!(
+            error("--noise-floor 0"),
+            "--noise-floor can only be used with bench."
+        );
+        assert_eq!(
+            error("bench --verify"),
+            "--verify can not be used with bench."
+        );
+        assert_eq!(error("bench --runs 0"), "--runs must be at least 1.");
+        assert_eq!(error("new-day"), "new-day requires a day.");
+        assert_eq!(
+            error("new-day 3 4"),
+            "Unexpected argument \"4\" after new-day 3."
+        );
+        assert_eq!(
+            error("new-day 30"),
+            "Day 30 is out of range, expected 1 to 25."
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::Result;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Where a sequence of states, each produced from the one before, starts
+/// repeating itself.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    /// How many states come before the first one that repeats.
+    pub start: usize,
+    /// How many states the repeating part is made of.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The index of the first state equal to the state at `index`, so that
+    /// far off states can be looked up among the ones already seen.
+    pub fn equivalent(&self, index: usize) -> usize {
+        if index < self.start {
+            index
+        } else {
+            self.start + (index - self.start) % self.period
+        }
+    }
+}
+
+/// Finds the cycle with Brent's algorithm, which only ever holds two states
+/// at a time.
+pub fn brent<T>(initial: T, mut step: impl FnMut(&T) -> Result<T>) -> Result<Cycle>
+where
+    T: Clone + PartialEq,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare)?;
+        period += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare)?;
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        start += 1;
+    }
+
+    Ok(Cycle { start, period })
+}
+
+/// Every state up to the point they start repeating, along with the cycle.
+#[derive(Debug)]
+pub struct History<T> {
+    pub cycle: Cycle,
+    pub states: Vec<T>,
+}
+
+impl<T> History<T> {
+    /// The state at `index`, however far past the recorded states it is.
+    pub fn nth(&self, index: usize) -> &T {
+        &self.states[self.cycle.equivalent(index)]
+    }
+}
+
+fn hash_of<T: Hash>(state: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Finds the cycle by remembering every state, stepping each one only once.
+///
+/// States are looked up by hash, but a match is only taken once the states
+/// themselves compare equal, so a collision can't give a wrong cycle.
+pub fn find<T>(initial: T, mut step: impl FnMut(&T) -> Result<T>) -> Result<History<T>>
+where
+    T: Hash + Eq,
+{
+    let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+
+    loop {
+        let hash = hash_of(&state);
+        let indices = seen.entry(hash).or_default();
+
+        if let Some(&start) = indices.iter().find(|&&index| states[index] == state) {
+            let cycle = Cycle {
+                start,
+                period: states.len() - start,
+            };
+            return Ok(History { cycle, states });
+        }
+
+        indices.push(states.len());
+        let next = step(&state)?;
+        states.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, find, Cycle};
+    use anyhow::Result;
+    use std::hash::{Hash, Hasher};
+
+    /// Steps through `start` states before looping over `period` more.
+    fn rho(start: usize, period: usize) -> impl FnMut(&usize) -> Result<usize> {
+        move |&state| {
+            Ok(if state + 1 == start + period {
+                start
+            } else {
+                state + 1
+            })
+        }
+    }
+
+    #[test]
+    fn brent_and_find_agree() {
+        for start in 0..6 {
+            for period in 1..6 {
+                let expected = Cycle { start, period };
+                assert_eq!(brent(0, rho(start, period)).unwrap(), expected);
+
+                let history = find(0, rho(start, period)).unwrap();
+                assert_eq!(history.cycle, expected);
+                assert_eq!(history.states, (0..start + period).collect::<Vec<_>>());
+            }
+        }
+    }
+
+    #[test]
+    fn looks_up_far_off_states() {
+        let history = find(0, rho(3, 4)).unwrap();
+        for index in 0..100 {
+            let expected = if index < 3 {
+                index
+            } else {
+                3 + (index - 3) % 4
+            };
+            assert_eq!(*history.nth(index), expected);
+        }
+    }
+
+    /// Hashes every value the same, so only equality tells them apart.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct Colliding(usize);
+
+    impl Hash for Colliding {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            0.hash(state);
+        }
+    }
+
+    #[test]
+    fn find_checks_equality_on_collisions() {
+        let mut step = rho(2, 3);
+        let history = find(Colliding(0), |state| Ok(Colliding(step(&state.0)?))).unwrap();
+        assert_eq!(
+            history.cycle,
+            Cycle {
+                start: 2,
+                period: 3
+            }
+        );
+        assert_eq!(history.states.len(), 5);
+    }
+
+    #[test]
+    fn passes_step_errors_on() {
+        let step = |_: &usize| -> Result<usize> { anyhow::bail!("stuck") };
+        assert!(brent(0, step).is_err());
+        assert!(find(0, step).is_err());
+    }
+}
@@ -1,17 +1,17 @@
-use crate::Solvable;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{anyhow, Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::{bail, Context, Result};
 
 use std::ops::Not;
-use std::str::FromStr;
 use strum_macros::{Display, EnumIs, EnumString};
 
 use std::collections::HashSet;
 
 #[derive(Copy, Clone, EnumString, EnumIs, Debug, Display)]
-enum Pipe {
+pub enum Pipe {
     #[strum(serialize = "|")]
     Vertical,
     #[strum(serialize = "-")]
@@ -30,27 +30,6 @@ enum Pipe {
     Start,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, EnumIs, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Not for Direction {
-    type Output = Self;
-
-    fn not(self) -> Self::Output {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Right => Direction::Left,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-        }
-    }
-}
-
 impl Pipe {
     pub fn is_top(&self) -> bool {
         self.is_top_left() || self.is_top_right()
@@ -136,14 +115,8 @@ impl Pipe {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
-struct Coords {
-    row: usize,
-    col: usize,
-}
-
 #[derive(EnumIs, Debug)]
-enum PathPosition {
+pub enum PathPosition {
     OutsidePath,
     OnPathPerp(Transition),
     OnPathAngled((Transition, Option<i32>)),
@@ -151,7 +124,7 @@ enum PathPosition {
 }
 
 #[derive(Copy, Clone, EnumIs, Debug)]
-enum Transition {
+pub enum Transition {
     Entered,
     Exited,
 }
@@ -168,165 +141,114 @@ impl Not for Transition {
 }
 
 #[derive(Debug)]
-struct Map {
-    pipes: Vec<Vec<Pipe>>,
-    rows_count: usize,
-    columns_count: usize,
-    path_coords: HashSet<Coords>,
-    inside_coords: HashSet<Coords>,
+pub struct Map {
+    pub pipes: Grid<Pipe>,
+    pub path_coords: HashSet<Point>,
+    pub inside_coords: HashSet<Point>,
+    pub start: Point,
 }
 
 impl Map {
-    pub fn neighbors(&self, coords: Coords, origin: Option<Direction>) -> Vec<(Coords, Direction)> {
-        let mut neighbors = Vec::new();
-
-        let top_row = coords.row.checked_sub(1);
-        let right_col = coords.col + 1;
-        let bottom_row = coords.row + 1;
-        let left_col = coords.col.checked_sub(1);
-
-        if let Some(top_row) = top_row {
-            let top = Coords {
-                row: top_row,
-                col: coords.col,
-            };
-            // if !origin.is_some_and(|origin| origin.is_up()) {
-            //     neighbors.push((top, Direction::Up));
-            // }
-        }
-        if right_col <= self.columns_count {
-            let right = Coords {
-                row: coords.row,
-                col: right_col,
-            };
-            // if !origin.is_some_and(|origin| origin.is_right()) {
-            //     neighbors.push((right, Direction::Right));
-            // }
-        }
-        if bottom_row <= self.rows_count {
-            let bottom = Coords {
-                row: bottom_row,
-                col: coords.col,
-            };
-            // if !origin.is_some_and(|origin| origin.is_down()) {
-            //     neighbors.push((bottom, Direction::Down));
-            // }
-        }
-        if let Some(left_col) = left_col {
-            let left = Coords {
-                row: coords.row,
-                col: left_col,
-            };
-            // if !origin.is_some_and(|origin| origin.is_left()) {
-            //     neighbors.push((left, Direction::Left));
-            // }
-        }
+    /// The pipes around `coords`, except back towards `origin`, along with
+    /// the direction they are in.
+    pub fn neighbors(&self, coords: Point, origin: Option<Direction>) -> Vec<(Point, Direction)> {
+        let in_bounds =
+            |point: &Point| point.x < self.pipes.width() && point.y < self.pipes.height();
 
-        neighbors
+        Direction::CARDINAL
+            .into_iter()
+            .filter(|direction| origin != Some(*direction))
+            .filter_map(|direction| {
+                let neighbor = coords.checked_step(direction).filter(in_bounds)?;
+                Some((neighbor, direction))
+            })
+            .collect()
     }
 
-    pub fn get_pipe(&self, coords: Coords) -> Option<Pipe> {
-        self.pipes
-            .get(coords.row)?
-            .get(coords.col)
-            .and_then(|coords| Some(coords.clone()))
+    pub fn get_pipe(&self, coords: Point) -> Option<Pipe> {
+        self.pipes.get(coords.x, coords.y).copied()
     }
 
     pub fn find_connection(
         &self,
         pipe: Pipe,
-        coords: Coords,
+        coords: Point,
         origin: Option<Direction>,
-    ) -> Option<(Coords, Direction)> {
+    ) -> Option<(Point, Direction)> {
         let neighbors = self.neighbors(coords, origin);
 
         neighbors
             .into_iter()
             .find(|(coords, direction)| {
-                self.get_pipe(coords.clone())
-                    .and_then(|other_pipe| pipe.can_connect(&other_pipe, direction).then(|| ()))
+                self.get_pipe(*coords)
+                    .and_then(|other_pipe| pipe.can_connect(&other_pipe, direction).then_some(()))
                     .is_some()
             })
-            .and_then(|(coords, direction)| Some((coords, !direction)))
+            .map(|(coords, direction)| (coords, !direction))
     }
 }
 
 pub struct Day10 {}
 
 impl Solvable for Day10 {
+    type Parsed = Map;
+
     fn get_day() -> u32 {
         10
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut start = Coords { row: 0, col: 0 };
-
-        let pipes = read_to_string(path)?
-            .lines()
-            .enumerate()
-            .map(|(row, line)| {
-                (|| {
-                    line.chars()
-                        .enumerate()
-                        .map(|(col, char)| {
-                            (|| {
-                                let pipe = Pipe::from_str(char.to_string().as_str())
-                                    .map_err(anyhow::Error::msg)?;
-                                if pipe.is_start() {
-                                    start = Coords { row, col };
-                                }
-                                Ok(pipe)
-                            })()
-                        })
-                        .collect::<Result<Vec<Pipe>>>()
-                })()
-            })
-            .collect::<Result<Vec<Vec<Pipe>>>>()?;
-
-        let rows_count = pipes.len();
-        let columns_count = pipes.get(0).context("No rows")?.len();
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let pipes: Grid<Pipe> = input.parse()?;
+        let start = pipes
+            .positions(Pipe::is_start)
+            .next()
+            .map(Point::from)
+            .ok_or_else(|| PuzzleError::malformed("No start pipe."))?;
 
-        let map = Map {
+        Ok(Map {
             pipes,
-            rows_count,
-            columns_count,
             path_coords: HashSet::new(),
             inside_coords: HashSet::new(),
-        };
+            start,
+        })
+    }
 
-        if debug {
-            println!("{:#?}", start);
-            println!("{:#?}", map);
-        }
+    fn solve_part_one(map: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let start = map.start;
+
+        trace!(trace, Verbose, "{:#?}", start);
+        trace!(trace, Verbose, "{:#?}", map);
 
-        let max_path = map.rows_count * map.columns_count;
+        let max_path = map.pipes.width() * map.pipes.height();
 
         let mut pipe = Pipe::Start;
-        let mut coords = start.clone();
+        let mut coords = start;
         let mut origin = None;
         let mut path_length = 0;
         loop {
             let connection = map
                 .find_connection(pipe, coords, origin)
-                .context("Could not find next connection.")?;
+                .ok_or_else(|| PuzzleError::no_solution("Could not find next connection."))?;
             coords = connection.0;
             origin = Some(connection.1);
             pipe = map.get_pipe(coords).context("Can't get pipe")?;
 
             path_length += 1;
 
-            if debug {
-                println!(
-                    "{}\tCoords: {:?}\n\tOrigin: {:?}\n\tPipe: {:?}",
-                    path_length, coords, origin, pipe
-                );
-            }
+            trace!(
+                trace,
+                Step,
+                "{}\tCoords: {:?}\n\tOrigin: {:?}\n\tPipe: {:?}",
+                path_length,
+                coords,
+                origin,
+                pipe
+            );
 
             if path_length >= max_path {
-                return Err(anyhow!("Took too many pipes"));
+                bail!(PuzzleError::no_solution(
+                    "The path never makes it back to the start."
+                ));
             }
 
             if pipe.is_start() {
@@ -336,81 +258,47 @@ impl Solvable for Day10 {
 
         let steps_to_furthest_pipe = path_length / 2;
 
-        let steps_to_furthest_pipe = i64::try_from(steps_to_furthest_pipe)?;
-        Ok(steps_to_furthest_pipe)
+        Ok(steps_to_furthest_pipe.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
+    fn solve_part_two(mut map: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let start = map.start;
 
-        let mut start = Coords { row: 0, col: 0 };
+        trace!(trace, Verbose, "{:#?}", start);
+        trace!(trace, Verbose, "{:#?}", map);
 
-        let pipes = read_to_string(path)?
-            .lines()
-            .enumerate()
-            .map(|(row, line)| {
-                (|| {
-                    line.chars()
-                        .enumerate()
-                        .map(|(col, char)| {
-                            (|| {
-                                let pipe = Pipe::from_str(char.to_string().as_str())
-                                    .map_err(anyhow::Error::msg)?;
-                                if pipe.is_start() {
-                                    start = Coords { row, col };
-                                }
-                                Ok(pipe)
-                            })()
-                        })
-                        .collect::<Result<Vec<Pipe>>>()
-                })()
-            })
-            .collect::<Result<Vec<Vec<Pipe>>>>()?;
-
-        let rows_count = pipes.len();
-        let columns_count = pipes.get(0).context("No rows")?.len();
-
-        let mut map = Map {
-            pipes,
-            rows_count,
-            columns_count,
-            path_coords: HashSet::new(),
-            inside_coords: HashSet::new(),
-        };
-
-        if debug {
-            println!("{:#?}", start);
-            println!("{:#?}", map);
-        }
-
-        let max_path = map.rows_count * map.columns_count;
+        let max_path = map.pipes.width() * map.pipes.height();
 
         let mut pipe = Pipe::Start;
-        let mut coords = start.clone();
+        let mut coords = start;
         let mut origin = None;
         let mut path_length = 0;
         loop {
             let connection = map
                 .find_connection(pipe, coords, origin)
-                .context("Could not find next connection.")?;
+                .ok_or_else(|| PuzzleError::no_solution("Could not find next connection."))?;
             coords = connection.0;
             origin = Some(connection.1);
             pipe = map.get_pipe(coords).context("Can't get pipe")?;
 
-            map.path_coords.insert(coords.clone());
+            map.path_coords.insert(coords);
 
             path_length += 1;
 
-            if debug {
-                println!(
-                    "{}\tCoords: {:?}\n\tOrigin: {:?}\n\tPipe: {:?}",
-                    path_length, coords, origin, pipe
-                );
-            }
+            trace!(
+                trace,
+                Step,
+                "{}\tCoords: {:?}\n\tOrigin: {:?}\n\tPipe: {:?}",
+                path_length,
+                coords,
+                origin,
+                pipe
+            );
 
             if path_length >= max_path {
-                return Err(anyhow!("Took too many pipes"));
+                bail!(PuzzleError::no_solution(
+                    "The path never makes it back to the start."
+                ));
             }
 
             if pipe.is_start() {
@@ -418,129 +306,213 @@ impl Solvable for Day10 {
             }
         }
 
-        if debug {
-            println!("{:#?}", map.path_coords);
-        }
-
-        let interior_tiles = map
-            .pipes
-            .iter()
-            .enumerate()
-            .fold(0, |interior_tiles, (row, cols)| {
-                interior_tiles
-                    + cols
-                        .iter()
-                        .enumerate()
-                        .fold(
-                            (0, PathPosition::OutsidePath),
-                            |(prev_interior_tiles, prev_path_position), (col, pipe)| {
-                                let coords = Coords { row, col };
-                                let is_path_pipe = map.path_coords.contains(&coords);
-
-                                if debug {
-                                    println!("\t{:?}", prev_path_position);
-                                    println!("{:?}", coords);
-                                }
-
-                                let curr_path_position = match (&prev_path_position, is_path_pipe) {
-                                    (PathPosition::OutsidePath, true) => {
-                                        if pipe.is_vertical() {
-                                            PathPosition::OnPathPerp(Transition::Entered)
-                                        } else {
-                                            PathPosition::OnPathAngled((
-                                                Transition::Entered,
-                                                Some(pipe.angle_count()),
-                                            ))
-                                        }
-                                    }
-                                    (PathPosition::InPath, true) => {
-                                        if pipe.is_vertical() {
-                                            PathPosition::OnPathPerp(Transition::Exited)
-                                        } else {
-                                            PathPosition::OnPathAngled((
-                                                Transition::Exited,
-                                                Some(pipe.angle_count()),
-                                            ))
-                                        }
-                                    }
-                                    (
-                                        PathPosition::OnPathAngled((transition, angle_count)),
-                                        true,
-                                    ) => {
-                                        if pipe.is_vertical() {
-                                            PathPosition::OnPathPerp(!*transition)
-                                        } else {
-                                            if pipe.is_horizontal() {
-                                                prev_path_position
-                                            } else if let Some(angle_count) = angle_count {
-                                                if angle_count + pipe.angle_count() == 0 {
-                                                    PathPosition::OnPathAngled((*transition, None))
+        trace!(trace, Verbose, "{:#?}", map.path_coords);
+
+        let interior_tiles =
+            map.pipes
+                .rows()
+                .enumerate()
+                .fold(0usize, |interior_tiles, (row, cols)| {
+                    interior_tiles
+                        + cols
+                            .iter()
+                            .enumerate()
+                            .fold(
+                                (0, PathPosition::OutsidePath),
+                                |(prev_interior_tiles, prev_path_position), (col, pipe)| {
+                                    let coords = Point::new(col, row);
+                                    let is_path_pipe = map.path_coords.contains(&coords);
+
+                                    trace!(trace, Step, "\t{:?}", prev_path_position);
+                                    trace!(trace, Step, "{:?}", coords);
+
+                                    let curr_path_position =
+                                        match (&prev_path_position, is_path_pipe) {
+                                            (PathPosition::OutsidePath, true) => {
+                                                if pipe.is_vertical() {
+                                                    PathPosition::OnPathPerp(Transition::Entered)
+                                                } else {
+                                                    PathPosition::OnPathAngled((
+                                                        Transition::Entered,
+                                                        Some(pipe.angle_count()),
+                                                    ))
+                                                }
+                                            }
+                                            (PathPosition::InPath, true) => {
+                                                if pipe.is_vertical() {
+                                                    PathPosition::OnPathPerp(Transition::Exited)
                                                 } else {
-                                                    PathPosition::OnPathAngled((!*transition, None))
+                                                    PathPosition::OnPathAngled((
+                                                        Transition::Exited,
+                                                        Some(pipe.angle_count()),
+                                                    ))
                                                 }
-                                            } else {
+                                            }
+                                            (
                                                 PathPosition::OnPathAngled((
-                                                    !*transition,
-                                                    Some(pipe.angle_count()),
-                                                ))
+                                                    transition,
+                                                    angle_count,
+                                                )),
+                                                true,
+                                            ) => {
+                                                if pipe.is_vertical() {
+                                                    PathPosition::OnPathPerp(!*transition)
+                                                } else {
+                                                    if pipe.is_horizontal() {
+                                                        prev_path_position
+                                                    } else if let Some(angle_count) = angle_count {
+                                                        if angle_count + pipe.angle_count() == 0 {
+                                                            PathPosition::OnPathAngled((
+                                                                *transition,
+                                                                None,
+                                                            ))
+                                                        } else {
+                                                            PathPosition::OnPathAngled((
+                                                                !*transition,
+                                                                None,
+                                                            ))
+                                                        }
+                                                    } else {
+                                                        PathPosition::OnPathAngled((
+                                                            !*transition,
+                                                            Some(pipe.angle_count()),
+                                                        ))
+                                                    }
+                                                }
                                             }
-                                        }
-                                    }
-                                    (PathPosition::OnPathAngled((transition, _)), false) => {
-                                        if transition.is_entered() {
-                                            map.inside_coords.insert(coords);
-                                            PathPosition::InPath
-                                        } else {
-                                            PathPosition::OutsidePath
-                                        }
-                                    }
-                                    (PathPosition::OnPathPerp(transition), true) => {
-                                        if pipe.is_vertical() {
-                                            PathPosition::OnPathPerp(!*transition)
-                                        } else {
-                                            PathPosition::OnPathAngled((
-                                                !*transition,
-                                                Some(pipe.angle_count()),
-                                            ))
-                                        }
-                                    }
-                                    (PathPosition::OnPathPerp(transition), false) => {
-                                        if transition.is_entered() {
-                                            map.inside_coords.insert(coords);
-                                            PathPosition::InPath
-                                        } else {
-                                            PathPosition::OutsidePath
-                                        }
+                                            (
+                                                PathPosition::OnPathAngled((transition, _)),
+                                                false,
+                                            ) => {
+                                                if transition.is_entered() {
+                                                    map.inside_coords.insert(coords);
+                                                    PathPosition::InPath
+                                                } else {
+                                                    PathPosition::OutsidePath
+                                                }
+                                            }
+                                            (PathPosition::OnPathPerp(transition), true) => {
+                                                if pipe.is_vertical() {
+                                                    PathPosition::OnPathPerp(!*transition)
+                                                } else {
+                                                    PathPosition::OnPathAngled((
+                                                        !*transition,
+                                                        Some(pipe.angle_count()),
+                                                    ))
+                                                }
+                                            }
+                                            (PathPosition::OnPathPerp(transition), false) => {
+                                                if transition.is_entered() {
+                                                    map.inside_coords.insert(coords);
+                                                    PathPosition::InPath
+                                                } else {
+                                                    PathPosition::OutsidePath
+                                                }
+                                            }
+                                            (PathPosition::InPath, false) => PathPosition::InPath,
+                                            (_, _) => prev_path_position,
+                                        };
+
+                                    if curr_path_position.is_in_path() {
+                                        map.inside_coords.insert(coords);
+                                        (prev_interior_tiles + 1, curr_path_position)
+                                    } else {
+                                        (prev_interior_tiles, curr_path_position)
                                     }
-                                    (PathPosition::InPath, false) => PathPosition::InPath,
-                                    (_, _) => prev_path_position,
-                                };
-
-                                if curr_path_position.is_in_path() {
-                                    map.inside_coords.insert(coords);
-                                    (prev_interior_tiles + 1, curr_path_position)
-                                } else {
-                                    (prev_interior_tiles, curr_path_position)
-                                }
-                            },
-                        )
-                        .0
-            });
-
-        if debug {
-            map.pipes.iter().enumerate().for_each(|(row, cols)| {
-                cols.iter().enumerate().for_each(|(col, pipe)| {
-                    let char = if map.inside_coords.contains(&Coords { row, col }) {
-                        String::from("I")
-                    } else {
-                        pipe.to_string()
-                    };
-                    print!("{}", char);
+                                },
+                            )
+                            .0
                 });
-                println!("");
-            })
-        }
 
-        Ok(interior_tiles)
+        trace!(
+            trace,
+            Verbose,
+            "{}",
+            map.pipes
+                .rows()
+                .enumerate()
+                .map(|(row, cols)| {
+                    cols.iter()
+                        .enumerate()
+                        .map(|(col, pipe)| {
+                            if map.inside_coords.contains(&Point::new(col, row)) {
+                                String::from("I")
+                            } else {
+                                pipe.to_string()
+                            }
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+
+        Ok(interior_tiles.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day10;
+    use crate::testing::example_tests;
+
+    const EXAMPLE_ONE: &str = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....
+";
+
+    const EXAMPLE_TWO: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+";
+
+    const EXAMPLE_THREE: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+
+    const EXAMPLE_FOUR: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+
+    const EXAMPLE_FIVE: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+";
+
+    example_tests! {
+        Day10,
+        part_one: [(EXAMPLE_ONE, 4), (EXAMPLE_TWO, 8)],
+        part_two: [(EXAMPLE_THREE, 4), (EXAMPLE_FOUR, 8), (EXAMPLE_FIVE, 10)],
     }
 }
@@ -1,229 +1,142 @@
-use crate::Solvable;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
 use anyhow::Result;
-use std::fs::read_to_string;
-use std::path::Path;
 
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug)]
-struct Galaxy {
-    x: usize,
-    y: usize,
+pub struct Image {
+    pub galaxies: Vec<Point>,
+    pub width: usize,
+    pub height: usize,
 }
 
-impl Galaxy {
-    pub fn distance(&self, other: &Self) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-}
-
-pub struct Day11 {}
-
-impl Solvable for Day11 {
-    fn get_day() -> u32 {
-        11
-    }
-
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        // Initial parse
-        let mut galaxies = Vec::new();
-        let mut populated_columns = HashSet::new();
-        let mut width = 0;
-
-        read_to_string(path)?
-            .lines()
-            .enumerate()
-            .fold(0, |vertical_expansion_count, (y, line)| {
-                width = line.len();
-
-                let row_has_galaxy =
-                    line.chars()
-                        .enumerate()
-                        .fold(false, |row_has_galaxy, (x, char)| {
-                            let is_galaxy = char == '#';
-                            if is_galaxy {
-                                galaxies.push(Galaxy {
-                                    x,
-                                    y: y + vertical_expansion_count,
-                                });
-                                populated_columns.insert(x);
-                            }
-                            row_has_galaxy || is_galaxy
-                        });
-
-                if row_has_galaxy {
-                    vertical_expansion_count
-                } else {
-                    vertical_expansion_count + 1
+impl Image {
+    /// Replaces every empty row and column with `expansion_factor` of them.
+    pub fn expand(&mut self, expansion_factor: usize, trace: &Trace) {
+        let populated_rows = self
+            .galaxies
+            .iter()
+            .map(|galaxy| galaxy.y)
+            .collect::<HashSet<usize>>();
+        let populated_columns = self
+            .galaxies
+            .iter()
+            .map(|galaxy| galaxy.x)
+            .collect::<HashSet<usize>>();
+
+        let expansion_counts = |populated: &HashSet<usize>, length: usize| {
+            let mut expansion_counts = HashMap::new();
+            (0..length).fold(0, |mut expansion_count, index| {
+                if !populated.contains(&index) {
+                    expansion_count += expansion_factor - 1;
                 }
+                expansion_counts.insert(index, expansion_count);
+                expansion_count
             });
+            expansion_counts
+        };
 
-        // Account for horizontal expansion
-        let mut horizontal_expansion_counts = HashMap::new();
-        (0..width).fold(0, |mut horizontal_expansion_count, x| {
-            if !populated_columns.contains(&x) {
-                horizontal_expansion_count += 1;
-            }
-            horizontal_expansion_counts.insert(x, horizontal_expansion_count);
-            horizontal_expansion_count
-        });
+        let vertical_expansion_counts = expansion_counts(&populated_rows, self.height);
+        let horizontal_expansion_counts = expansion_counts(&populated_columns, self.width);
 
-        if debug {
-            println!("horizontal_expansion_counts");
-            println!("{:#?}", horizontal_expansion_counts);
-            println!("");
-        }
+        trace!(trace, Verbose, "horizontal_expansion_counts");
+        trace!(trace, Verbose, "{:#?}", horizontal_expansion_counts);
 
-        galaxies.iter_mut().for_each(|galaxy| {
-            if horizontal_expansion_counts.contains_key(&galaxy.x) {
-                if debug {
-                    println!("Before: {:?}", galaxy);
-                }
+        self.galaxies.iter_mut().for_each(|galaxy| {
+            trace!(trace, Step, "Before: {:?}", galaxy);
 
-                galaxy.x += horizontal_expansion_counts.get(&galaxy.x).unwrap_or(&0);
+            galaxy.x += horizontal_expansion_counts.get(&galaxy.x).unwrap_or(&0);
+            galaxy.y += vertical_expansion_counts.get(&galaxy.y).unwrap_or(&0);
 
-                if debug {
-                    println!("After: {:?}", galaxy);
-                    println!("");
-                }
-            }
+            trace!(trace, Step, "After: {:?}", galaxy);
         });
-
-        if debug {
-            galaxies.iter().for_each(|galaxy| {
-                println!("{:?}", galaxy);
-            });
-            println!("");
-        }
-
-        // Get sum of min dists
-        let sum_of_min_dists =
-            galaxies
-                .iter()
-                .enumerate()
-                .fold(0, |sum, (index, starting_galaxy)| {
-                    if debug {
-                        println!("");
-                    }
-                    sum + galaxies
-                        .iter()
-                        .skip(index + 1)
-                        .fold(0, |sum, ending_galaxy| {
-                            let dist = starting_galaxy.distance(&ending_galaxy);
-                            if debug {
-                                println!("{:?} -> {:?} = {}", starting_galaxy, ending_galaxy, dist);
-                            }
-                            sum + dist
-                        })
-                });
-
-        let sum_of_min_dists = i64::try_from(sum_of_min_dists)?;
-        Ok(sum_of_min_dists)
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
+    pub fn sum_of_min_dists(&self, trace: &Trace) -> usize {
+        self.galaxies
+            .iter()
+            .enumerate()
+            .fold(0, |sum, (index, starting_galaxy)| {
+                sum + self
+                    .galaxies
+                    .iter()
+                    .skip(index + 1)
+                    .fold(0, |sum, ending_galaxy| {
+                        let dist = starting_galaxy.manhattan(ending_galaxy);
+                        trace!(
+                            trace,
+                            Step,
+                            "{:?} -> {:?} = {}",
+                            starting_galaxy,
+                            ending_galaxy,
+                            dist
+                        );
+                        sum + dist
+                    })
+            })
+    }
+}
 
-        let expansion_factor = 1_000_000;
+pub struct Day11 {}
 
-        // Initial parse
-        let mut galaxies = Vec::new();
-        let mut populated_columns = HashSet::new();
-        let mut width = 0;
+impl Solvable for Day11 {
+    type Parsed = Image;
 
-        read_to_string(path)?
-            .lines()
-            .enumerate()
-            .fold(0, |vertical_expansion_count, (y, line)| {
-                width = line.len();
-
-                let row_has_galaxy =
-                    line.chars()
-                        .enumerate()
-                        .fold(false, |row_has_galaxy, (x, char)| {
-                            let is_galaxy = char == '#';
-                            if is_galaxy {
-                                galaxies.push(Galaxy {
-                                    x,
-                                    y: y + vertical_expansion_count,
-                                });
-                                populated_columns.insert(x);
-                            }
-                            row_has_galaxy || is_galaxy
-                        });
-
-                if row_has_galaxy {
-                    vertical_expansion_count
-                } else {
-                    vertical_expansion_count + expansion_factor - 1
-                }
-            });
+    fn get_day() -> u32 {
+        11
+    }
 
-        // Account for horizontal expansion
-        let mut horizontal_expansion_counts = HashMap::new();
-        (0..width).fold(0, |mut horizontal_expansion_count, x| {
-            if !populated_columns.contains(&x) {
-                horizontal_expansion_count += expansion_factor - 1;
-            }
-            horizontal_expansion_counts.insert(x, horizontal_expansion_count);
-            horizontal_expansion_count
-        });
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let grid: Grid<char> = input.parse()?;
+
+        Ok(Image {
+            galaxies: grid
+                .positions(|char| *char == '#')
+                .map(Point::from)
+                .collect(),
+            width: grid.width(),
+            height: grid.height(),
+        })
+    }
 
-        if debug {
-            println!("horizontal_expansion_counts");
-            println!("{:#?}", horizontal_expansion_counts);
-            println!("");
-        }
+    fn solve_part_one(mut image: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        image.expand(2, trace);
 
-        galaxies.iter_mut().for_each(|galaxy| {
-            if horizontal_expansion_counts.contains_key(&galaxy.x) {
-                if debug {
-                    println!("Before: {:?}", galaxy);
-                }
+        Ok(image.sum_of_min_dists(trace).into())
+    }
 
-                galaxy.x += horizontal_expansion_counts.get(&galaxy.x).unwrap_or(&0);
+    fn solve_part_two(mut image: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        image.expand(1_000_000, trace);
 
-                if debug {
-                    println!("After: {:?}", galaxy);
-                    println!("");
-                }
-            }
-        });
+        Ok(image.sum_of_min_dists(trace).into())
+    }
+}
 
-        if debug {
-            galaxies.iter().for_each(|galaxy| {
-                println!("{:?}", galaxy);
-            });
-            println!("");
-        }
-
-        // Get sum of min dists
-        let sum_of_min_dists =
-            galaxies
-                .iter()
-                .enumerate()
-                .fold(0, |sum, (index, starting_galaxy)| {
-                    if debug {
-                        println!("");
-                    }
-                    sum + galaxies
-                        .iter()
-                        .skip(index + 1)
-                        .fold(0, |sum, ending_galaxy| {
-                            let dist = starting_galaxy.distance(&ending_galaxy);
-                            if debug {
-                                println!("{:?} -> {:?} = {}", starting_galaxy, ending_galaxy, dist);
-                            }
-                            sum + dist
-                        })
-                });
-
-        let sum_of_min_dists = i64::try_from(sum_of_min_dists)?;
-        Ok(sum_of_min_dists)
+#[cfg(test)]
+mod tests {
+    use super::Day11;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+    example_tests! {
+        Day11,
+        part_one: [(EXAMPLE, 374)],
+        part_two: [(EXAMPLE, 82000210)],
     }
 }
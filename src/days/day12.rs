@@ -1,14 +1,13 @@
-use crate::Solvable;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::Result;
 
-use std::str::FromStr;
 use strum_macros::{Display, EnumIs, EnumString};
 
 #[derive(Copy, Clone, EnumString, EnumIs, Debug, Display)]
-enum Spring {
+pub enum Spring {
     #[strum(serialize = ".")]
     Operational,
     #[strum(serialize = "#")]
@@ -17,174 +16,149 @@ enum Spring {
     Unknown,
 }
 
+/// One row of the condition records.
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub springs: Vec<Spring>,
+    /// The size of each contiguous group of damaged springs, in order.
+    pub damaged_springs: Vec<u32>,
+}
+
+impl Record {
+    /// Repeats the springs `copies` times joined by unknown springs, and the
+    /// groups `copies` times.
+    pub fn unfold(&self, copies: usize) -> Self {
+        let springs = (0..copies)
+            .flat_map(|copy| {
+                let separator = (copy > 0).then_some(Spring::Unknown);
+                separator.into_iter().chain(self.springs.iter().copied())
+            })
+            .collect();
+
+        Record {
+            springs,
+            damaged_springs: self.damaged_springs.repeat(copies),
+        }
+    }
+
+    /// Counts the ways to replace the unknown springs so the damaged groups
+    /// match.
+    ///
+    /// `counts[index][group]` is the number of arrangements of the springs
+    /// from `index` on that hold exactly the groups from `group` on, filled
+    /// in from the end of the row.
+    pub fn arrangements(&self) -> u64 {
+        let springs = &self.springs;
+        let groups = &self.damaged_springs;
+
+        let mut counts = vec![vec![0u64; groups.len() + 1]; springs.len() + 1];
+        counts[springs.len()][groups.len()] = 1;
+
+        for index in (0..springs.len()).rev() {
+            for group in 0..=groups.len() {
+                let mut count = 0;
+
+                if !springs[index].is_damaged() {
+                    count += counts[index + 1][group];
+                }
+
+                if let Some(&length) = groups.get(group) {
+                    let end = index + length as usize;
+                    let fits = !springs[index].is_operational()
+                        && end <= springs.len()
+                        && springs[index..end]
+                            .iter()
+                            .all(|spring| !spring.is_operational())
+                        && !springs.get(end).is_some_and(|spring| spring.is_damaged());
+
+                    if fits {
+                        count += counts[(end + 1).min(springs.len())][group + 1];
+                    }
+                }
+
+                counts[index][group] = count;
+            }
+        }
+
+        counts[0][0]
+    }
+}
+
+fn sum_of_arrangements(records: &[Record], trace: &Trace) -> u64 {
+    records
+        .iter()
+        .map(|record| {
+            let arrangements = record.arrangements();
+
+            trace!(
+                trace,
+                Step,
+                "{} {:?} = {}",
+                record
+                    .springs
+                    .iter()
+                    .map(Spring::to_string)
+                    .collect::<String>(),
+                record.damaged_springs,
+                arrangements
+            );
+
+            arrangements
+        })
+        .sum()
+}
+
 pub struct Day12 {}
 
 impl Solvable for Day12 {
+    type Parsed = Vec<Record>;
+
     fn get_day() -> u32 {
         12
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let records = read_to_string(path)?
-            .lines()
-            .map(|line| {
-                (|| {
-                    let (springs, damaged_springs) = line.split_once(" ").context("")?;
-                    let springs = springs
-                        .chars()
-                        .map(|char| {
-                            (|| {
-                                Spring::from_str(char.to_string().as_str())
-                                    .map_err(anyhow::Error::msg)
-                            })()
-                        })
-                        .collect::<Result<Vec<Spring>>>()?;
-
-                    let damaged_springs = damaged_springs
-                        .split_terminator(",")
-                        .map(|char| (|| char.parse().map_err(anyhow::Error::msg))())
-                        .collect::<Result<Vec<u32>>>()?;
-
-                    Ok((springs, damaged_springs))
-                })()
-            })
-            .collect::<Result<Vec<(Vec<Spring>, Vec<u32>)>>>()?;
-
-        let sum = records.iter().fold(0, |sum, (springs, damaged_springs)| {
-            let question_count = springs.iter().filter(|spring| spring.is_unknown()).count();
-            let question_count = u32::try_from(question_count).unwrap_or(0);
-
-            sum + (0..(2usize.pow(question_count)))
-                .filter(|permutation_index| {
-                    let mut success = true;
-                    let mut springs = springs.clone();
-                    springs
-                        .iter_mut()
-                        .filter(|spring| spring.is_unknown())
-                        .enumerate()
-                        .for_each(|(index, spring)| {
-                            let index = u32::try_from(index).unwrap_or(0);
-
-                            if permutation_index & 2usize.pow(index) > 0 {
-                                *spring = Spring::Damaged;
-                            } else {
-                                *spring = Spring::Operational;
-                            }
-                        });
-
-                    let mut damaged_springs_iter = damaged_springs.iter();
-                    let damaged_count = springs.iter().fold(0, |damaged_count, spring| {
-                        if spring.is_damaged() {
-                            damaged_count + 1
-                        } else {
-                            if damaged_count > 0 {
-                                let expected_count = damaged_springs_iter.next().unwrap_or(&0);
-                                if expected_count != &damaged_count {
-                                    success = false;
-                                }
-                            }
-                            0
-                        }
-                    });
-
-                    if damaged_count > 0 {
-                        let expected_count = damaged_springs_iter.next().unwrap_or(&0);
-                        if expected_count != &damaged_count {
-                            success = false;
-                        }
-                    }
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| {
+            let (springs, damaged_springs) = line.split_once(" ")?;
 
-                    if let Some(_) = damaged_springs_iter.next() {
-                        success = false;
-                    }
+            Ok(Record {
+                springs: springs.cells()?,
+                damaged_springs: damaged_springs.list(",")?,
+            })
+        })
+    }
 
-                    if debug && success {
-                        println!("{:?}", damaged_springs);
-                        println!("{:?}\n", springs);
-                    }
+    fn solve_part_one(records: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        Ok(sum_of_arrangements(&records, trace).into())
+    }
 
-                    success
-                })
-                .count()
-        });
+    fn solve_part_two(records: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let records = records
+            .iter()
+            .map(|record| record.unfold(5))
+            .collect::<Vec<Record>>();
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum_of_arrangements(&records, trace).into())
     }
+}
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        Self::solve_part_one(debug)
+#[cfg(test)]
+mod tests {
+    use super::Day12;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+    example_tests! {
+        Day12,
+        part_one: [(EXAMPLE, 21)],
+        part_two: [(EXAMPLE, 525152)],
     }
-
-    // fn solve_part_two(_debug: bool) -> Result<i64> {
-    //     let path = format!("src/inputs/day{}.txt", Self::get_day());
-    //     let path = Path::new(&path);
-
-    //     let mut records = read_to_string(path)?
-    //         .lines()
-    //         .map(|line| {
-    //             (|| {
-    //                 let (springs, damaged_springs) = line.split_once(" ").context("")?;
-    //                 let mut springs = springs
-    //                     .chars()
-    //                     .map(|char| {
-    //                         (|| {
-    //                             Spring::from_str(char.to_string().as_str())
-    //                                 .map_err(anyhow::Error::msg)
-    //                         })()
-    //                     })
-    //                     .collect::<Result<Vec<Spring>>>()?;
-
-    //                 springs.push(Spring::Operational);
-
-    //                 let damaged_springs = damaged_springs
-    //                     .split_terminator(",")
-    //                     .map(|char| (|| char.parse().map_err(anyhow::Error::msg))())
-    //                     .collect::<Result<Vec<u32>>>()?;
-
-    //                 Ok((springs, damaged_springs))
-    //             })()
-    //         })
-    //         .collect::<Result<Vec<(Vec<Spring>, Vec<u32>)>>>()?;
-
-    //     let sum = records.iter().fold(0, |sum, (springs, damaged_springs)| {
-    //         let total_damage_count: usize =
-    //             usize::try_from(damaged_springs.iter().sum::<u32>()).unwrap_or_default();
-    //         let max_slide = springs.len() - total_damage_count - damaged_springs.len() + 1;
-
-    //         let mut prev_damaged = Vec::new();
-    //         springs.iter().fold(0, |prev_damaged_count, spring| {
-    //             let mut damaged_count = prev_damaged_count;
-    //             prev_damaged.push(damaged_count);
-
-    //             if spring.is_damaged() {
-    //                 damaged_count += 1;
-    //             }
-    //             damaged_count
-    //         });
-
-    //         damaged_springs.iter().fold(0, |curr_damaged_springs_count, damaged_springs_count| {
-    //             springs.iter().enumerate().fold(0, |curr_damaged_springs_count, (spring_index, spring)| {
-    //                 match spring {
-    //                     Spring::Operational => {
-    //                         0
-    //                     },
-    //                     Spring::Damaged => {
-    //                         curr_damaged_springs_count + 1
-    //                     },
-    //                     Spring::Unknown => {
-
-    //                     }
-    //                 }
-    //             })
-    //         });
-    //         0
-    //     });
-
-    //     let sum = i64::try_from(sum)?;
-    //     Ok(sum)
-    // }
 }
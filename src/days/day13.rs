@@ -1,14 +1,14 @@
-use crate::Solvable;
+use crate::grid::Grid;
+use crate::parse::sections;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::Result;
 
-use std::str::FromStr;
 use strum_macros::{Display, EnumIs, EnumString};
 
 #[derive(Copy, Clone, PartialEq, EnumString, EnumIs, Debug, Display)]
-enum Tile {
+pub enum Tile {
     #[strum(serialize = ".")]
     Ash,
     #[strum(serialize = "#")]
@@ -16,41 +16,23 @@ enum Tile {
 }
 
 #[derive(Debug)]
-struct Ground {
-    tiles: Vec<Vec<Tile>>,
+pub struct Ground {
+    pub tiles: Grid<Tile>,
 }
 
 impl Ground {
-    // fn display_grid(&self, highlights: Vec<(usize, usize)>) {
-    //     for (y, row) in self.tiles.iter().enumerate() {
-    //         for (x, tile) in row.iter().enumerate() {
-    //             if highlights.iter().any(|(hx, hy)| hx == &x && hy == &y) {
-    //                 match tile {
-    //                     Tile::Ash => print!(";"),
-    //                     Tile::Rocks => print!("$")
-    //                 }
-    //             }
-    //             print!("{}", tile);
-    //         }
-    //         println!("");
-    //     }
-    //     println!("");
-    // }
-    
-    fn get_tile(&self, x: usize, y: usize) -> Option<Tile> {
-        self.tiles.get(y)?.get(x).map(|tile| tile.clone())
+    pub fn get_tile(&self, x: usize, y: usize) -> Option<Tile> {
+        self.tiles.get(x, y).copied()
     }
 
-    fn vertical_mirror(&self) -> Option<usize> {
-        let height = self.tiles.len();
-        let width = self.tiles.get(0)?.len();
+    pub fn vertical_mirror(&self) -> Option<usize> {
+        let height = self.tiles.height();
+        let width = self.tiles.width();
 
         let mut res = None;
         'col: for x in 1..width {
             for y in 0..height {
                 for (xl, xr) in (0..x).rev().zip(x..width) {
-                    // println!("({}, {}):{} - {} : {}", xl, xr, y, self.get_tile(xl, y)?, self.get_tile(xr, y)?);
-
                     if self.get_tile(xl, y)? != self.get_tile(xr, y)? {
                         continue 'col;
                     }
@@ -63,17 +45,14 @@ impl Ground {
         res
     }
 
-    fn horizontal_mirror(&self) -> Option<usize> {
-        let height = self.tiles.len();
-        let width = self.tiles.get(0)?.len();
+    pub fn horizontal_mirror(&self) -> Option<usize> {
+        let height = self.tiles.height();
+        let width = self.tiles.width();
 
         let mut res = None;
         'row: for y in 1..height {
             for x in 0..width {
                 for (yu, yd) in (0..y).rev().zip(y..height) {
-                    // println!("{}:({}, {}) - {} : {}", x, yu, yd, self.get_tile(x, yu)?, self.get_tile(x, yd)?);
-                    // self.display_grid(vec![(x, yu), (x, yd)]);
-
                     if self.get_tile(x, yu)? != self.get_tile(x, yd)? {
                         continue 'row;
                     }
@@ -86,9 +65,9 @@ impl Ground {
         res
     }
 
-    fn vertical_mirror_smudges(&self) -> Option<usize> {
-        let height = self.tiles.len();
-        let width = self.tiles.get(0)?.len();
+    pub fn vertical_mirror_smudges(&self) -> Option<usize> {
+        let height = self.tiles.height();
+        let width = self.tiles.width();
 
         let mut res = None;
         'col: for x in 1..width {
@@ -96,8 +75,6 @@ impl Ground {
 
             for y in 0..height {
                 for (xl, xr) in (0..x).rev().zip(x..width) {
-                    // println!("({}, {}):{} - {} : {}", xl, xr, y, self.get_tile(xl, y)?, self.get_tile(xr, y)?);
-
                     if self.get_tile(xl, y)? != self.get_tile(xr, y)? {
                         if smudge {
                             continue 'col;
@@ -117,9 +94,9 @@ impl Ground {
         res
     }
 
-    fn horizontal_mirror_smudges(&self) -> Option<usize> {
-        let height = self.tiles.len();
-        let width = self.tiles.get(0)?.len();
+    pub fn horizontal_mirror_smudges(&self) -> Option<usize> {
+        let height = self.tiles.height();
+        let width = self.tiles.width();
 
         let mut res = None;
         'row: for y in 1..height {
@@ -127,9 +104,6 @@ impl Ground {
 
             for x in 0..width {
                 for (yu, yd) in (0..y).rev().zip(y..height) {
-                    // println!("{}:({}, {}) - {} : {}", x, yu, yd, self.get_tile(x, yu)?, self.get_tile(x, yd)?);
-                    // self.display_grid(vec![(x, yu), (x, yd)]);
-
                     if self.get_tile(x, yu)? != self.get_tile(x, yd)? {
                         if smudge {
                             continue 'row;
@@ -153,41 +127,25 @@ impl Ground {
 pub struct Day13 {}
 
 impl Solvable for Day13 {
+    type Parsed = Vec<Ground>;
+
     fn get_day() -> u32 {
         13
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let patterns: Vec<Ground> = read_to_string(path)?
-            .lines()
-            .try_fold(Vec::<Ground>::new(), |mut patterns, line| {
-                if line.is_empty() {
-                    patterns.push(Ground { tiles: Vec::new() });
-                    return Ok(patterns);
-                }
-                if patterns.len() == 0 {
-                    patterns.push(Ground { tiles: Vec::new() });
-                }
-
-                let pattern = patterns.last_mut().context("No last pattern")?;
-                let pattern_line = line
-                    .chars()
-                    .map(|char| {
-                        Tile::from_str(char.to_string().as_str()).map_err(anyhow::Error::msg)
-                    })
-                    .collect::<std::result::Result<Vec<Tile>, anyhow::Error>>()?;
-                pattern.tiles.push(pattern_line);
-
-                return Ok(patterns);
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        sections(input)
+            .into_iter()
+            .map(|section| {
+                Ok(Ground {
+                    tiles: section.parse(|pattern| Ok(pattern.parse()?))?,
+                })
             })
-            .map_err(anyhow::Error::msg::<anyhow::Error>)?;
+            .collect()
+    }
 
-        if debug {
-            println!("{:?}", patterns);
-        }
+    fn solve_part_one(patterns: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:?}", patterns);
 
         let sum: usize = patterns
             .iter()
@@ -199,41 +157,11 @@ impl Solvable for Day13 {
             })
             .sum();
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let patterns: Vec<Ground> = read_to_string(path)?
-            .lines()
-            .try_fold(Vec::<Ground>::new(), |mut patterns, line| {
-                if line.is_empty() {
-                    patterns.push(Ground { tiles: Vec::new() });
-                    return Ok(patterns);
-                }
-                if patterns.len() == 0 {
-                    patterns.push(Ground { tiles: Vec::new() });
-                }
-
-                let pattern = patterns.last_mut().context("No last pattern")?;
-                let pattern_line = line
-                    .chars()
-                    .map(|char| {
-                        Tile::from_str(char.to_string().as_str()).map_err(anyhow::Error::msg)
-                    })
-                    .collect::<std::result::Result<Vec<Tile>, anyhow::Error>>()?;
-                pattern.tiles.push(pattern_line);
-
-                return Ok(patterns);
-            })
-            .map_err(anyhow::Error::msg::<anyhow::Error>)?;
-
-        if debug {
-            println!("{:?}", patterns);
-        }
+    fn solve_part_two(patterns: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:?}", patterns);
 
         let sum: usize = patterns
             .iter()
@@ -245,7 +173,36 @@ impl Solvable for Day13 {
             })
             .sum();
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day13;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.##..##.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+    example_tests! {
+        Day13,
+        part_one: [(EXAMPLE, 405)],
+        part_two: [(EXAMPLE, 400)],
     }
 }
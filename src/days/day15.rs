@@ -1,129 +1,122 @@
-use crate::Solvable;
+use crate::parse::sections;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::{bail, Context, Result};
 
 use std::collections::HashMap;
 
 pub struct Day15 {}
 
 impl Solvable for Day15 {
+    type Parsed = Vec<String>;
+
     fn get_day() -> u32 {
         15
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let [sequence] = sections(input)[..] else {
+            bail!(PuzzleError::malformed(
+                "Expected a single initialization sequence."
+            ));
+        };
 
-        let sum = read_to_string(path)?
-            .lines()
-            .next()
-            .context("")?
-            .split(",")
-            .fold(0, |sum, chars| {
-                let hash = chars
-                    .chars()
-                    .fold(0, |hash, char| ((hash + char as u64) * 17) % 256);
+        Ok(sequence.single_line()?.list(",")?)
+    }
 
-                let hash = i64::try_from(hash).unwrap_or_default();
+    fn solve_part_one(steps: Self::Parsed, _: &Trace) -> Result<Answer> {
+        let sum = steps.iter().fold(0, |sum, chars| {
+            let hash = chars
+                .chars()
+                .fold(0, |hash, char| ((hash + char as u64) * 17) % 256);
 
-                sum + hash
-            });
+            sum + hash
+        });
 
-        Ok(sum)
+        Ok(sum.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
+    fn solve_part_two(steps: Self::Parsed, trace: &Trace) -> Result<Answer> {
         let mut boxes: HashMap<u32, Vec<(String, u32)>> = HashMap::new();
 
-        read_to_string(path)?
-            .lines()
-            .next()
-            .context("")?
-            .split(",")
-            .try_for_each(|chars| {
-                let operation_index = chars
-                    .chars()
-                    .position(|char| char == '=' || char == '-')
-                    .unwrap();
-
-                let label = chars.chars().take(operation_index).collect();
-
-                let hash = chars
-                    .chars()
-                    .take(operation_index)
-                    .fold(0, |hash, char| ((hash + char as u32) * 17) % 256);
-
-                let operation = chars
-                    .chars()
-                    .skip(operation_index)
-                    .take(1)
-                    .collect::<Vec<char>>()
-                    .first()
-                    .context("No operation")?
-                    .clone();
-
-                let curr_box = if let Some(curr_box) = boxes.get_mut(&hash) {
-                    curr_box
-                } else {
-                    boxes.insert(hash.clone(), Vec::new());
-                    boxes.get_mut(&hash).unwrap()
-                };
-
-                let existing_lens = curr_box
-                    .iter()
-                    .enumerate()
-                    .find(|eval_box| eval_box.1 .0 == label)
-                    .and_then(|lens| Some((lens.0, lens.1 .1)));
-
-                let new_lens_power = chars
-                    .chars()
-                    .skip(operation_index + 1)
-                    .take(1)
-                    .collect::<Vec<char>>()
-                    .first()
-                    .and_then(|power| power.to_digit(10))
-                    .context("");
-
-                if debug {
-                    println!("{}{}{:?}", label, operation, new_lens_power);
-                }
-
-                if let Some(existing_lens) = existing_lens {
-                    if let Some(lens) = curr_box.get_mut(existing_lens.0) {
-                        if operation == '=' {
-                            lens.1 = new_lens_power?;
-                        }
-
-                        if operation == '-' {
-                            curr_box.remove(existing_lens.0);
-                        }
-                    }
-                } else {
+        steps.iter().try_for_each(|chars| {
+            let operation_index = chars
+                .chars()
+                .position(|char| char == '=' || char == '-')
+                .ok_or_else(|| {
+                    PuzzleError::malformed(format!("Step \"{}\" has no operation.", chars))
+                })?;
+
+            let label = chars.chars().take(operation_index).collect();
+
+            let hash = chars
+                .chars()
+                .take(operation_index)
+                .fold(0, |hash, char| ((hash + char as u32) * 17) % 256);
+
+            let operation = *chars
+                .chars()
+                .skip(operation_index)
+                .take(1)
+                .collect::<Vec<char>>()
+                .first()
+                .context("No operation")?;
+
+            let curr_box = if let Some(curr_box) = boxes.get_mut(&hash) {
+                curr_box
+            } else {
+                boxes.insert(hash, Vec::new());
+                boxes.get_mut(&hash).unwrap()
+            };
+
+            let existing_lens = curr_box
+                .iter()
+                .enumerate()
+                .find(|eval_box| eval_box.1 .0 == label)
+                .map(|lens| (lens.0, lens.1 .1));
+
+            let new_lens_power = chars
+                .chars()
+                .skip(operation_index + 1)
+                .take(1)
+                .collect::<Vec<char>>()
+                .first()
+                .and_then(|power| power.to_digit(10))
+                .ok_or_else(|| {
+                    PuzzleError::malformed(format!("Step \"{}\" has no focal length.", chars))
+                });
+
+            trace!(trace, Step, "{}{}{:?}", label, operation, new_lens_power);
+
+            if let Some(existing_lens) = existing_lens {
+                if let Some(lens) = curr_box.get_mut(existing_lens.0) {
                     if operation == '=' {
-                        curr_box.push((label, new_lens_power?));
+                        lens.1 = new_lens_power?;
                     }
-                }
 
-                if curr_box.len() == 0 {
-                    boxes.remove(&hash);
+                    if operation == '-' {
+                        curr_box.remove(existing_lens.0);
+                    }
                 }
+            } else {
+                if operation == '=' {
+                    curr_box.push((label, new_lens_power?));
+                }
+            }
+
+            if curr_box.is_empty() {
+                boxes.remove(&hash);
+            }
 
-                // if debug {
-                //     println!("{:?}", boxes);
-                // }
+            // if debug {
+            //     println!("{:?}", boxes);
+            // }
 
-                Ok::<(), anyhow::Error>(())
-            })?;
+            Ok::<(), anyhow::Error>(())
+        })?;
 
-        if debug {
-            println!("{:?}", boxes);
-        }
+        trace!(trace, Verbose, "{:?}", boxes);
 
         let sum = boxes.into_iter().fold(0, |acc, curr_box| {
             let box_pos = curr_box.0 + 1;
@@ -132,11 +125,23 @@ impl Solvable for Day15 {
                 let lens_power = lens.1 .1;
                 (lens_pos as u32 * lens_power) + acc
             });
-            (box_pos as u32 * box_power) + acc
+            (box_pos * box_power) + acc
         });
 
-        let sum = i64::try_from(sum).context("")?;
+        Ok(sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day15;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
 
-        Ok(sum)
+    example_tests! {
+        Day15,
+        part_one: [(EXAMPLE, 1320)],
+        part_two: [(EXAMPLE, 145)],
     }
 }
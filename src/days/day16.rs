@@ -1,15 +1,13 @@
-use crate::Solvable;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
-use std::str::FromStr;
+use anyhow::{bail, Result};
 use strum_macros::{Display, EnumIs, EnumString};
 
-use std::collections::HashMap;
-
 #[derive(Copy, Clone, EnumString, EnumIs, Debug, Display, Hash)]
-enum Tile {
+pub enum Tile {
     #[strum(serialize = ".")]
     Empty,
     #[strum(serialize = "-")]
@@ -22,232 +20,195 @@ enum Tile {
     BackwardMirror,
 }
 
-enum Direction {
-    Left,
-    Right,
-    Up,
-    Down,
-}
-
-struct Coord {
-    x: usize,
-    y: usize,
+impl Tile {
+    /// The directions a beam leaves this tile in after entering it heading
+    /// `direction`.
+    pub fn exits(&self, direction: Direction) -> Vec<Direction> {
+        let horizontal = matches!(direction, Direction::Left | Direction::Right);
+
+        match self {
+            Tile::Empty => vec![direction],
+            Tile::ForwardMirror if horizontal => vec![direction.turn_left()],
+            Tile::ForwardMirror => vec![direction.turn_right()],
+            Tile::BackwardMirror if horizontal => vec![direction.turn_right()],
+            Tile::BackwardMirror => vec![direction.turn_left()],
+            Tile::HorizontalSplitter if !horizontal => vec![Direction::Left, Direction::Right],
+            Tile::VerticalSplitter if horizontal => vec![Direction::Up, Direction::Down],
+            Tile::HorizontalSplitter | Tile::VerticalSplitter => vec![direction],
+        }
+    }
 }
 
-fn new_coord(coord: Coord, direction: Direction) -> Option<Coord> {
+/// Which directions a beam has already passed through a tile in, one bit each.
+fn direction_bit(direction: Direction) -> Result<u8, PuzzleError> {
     match direction {
-        Direction::Left => coord
-            .x
-            .checked_sub(1)
-            .and_then(|x| Some(Coord { x, y: coord.y })),
-        Direction::Right => coord
-            .x
-            .checked_add(1)
-            .and_then(|x| Some(Coord { x, y: coord.y })),
-        Direction::Up => coord
-            .y
-            .checked_sub(1)
-            .and_then(|y| Some(Coord { x: coord.x, y })),
-        Direction::Down => coord
-            .y
-            .checked_add(1)
-            .and_then(|y| Some(Coord { x: coord.x, y })),
+        Direction::Up => Ok(1),
+        Direction::Right => Ok(2),
+        Direction::Down => Ok(4),
+        Direction::Left => Ok(8),
+        _ => Err(PuzzleError::invariant(format!(
+            "Beams only travel in cardinal directions, not {:?}.",
+            direction
+        ))),
     }
 }
 
-fn get_energized_tiles(mut grid: Vec<Vec<(Tile, bool)>>, coord: Coord, direction: Direction) {
-    let mut row = match grid.get_mut(coord.y) {
-        None => return,
-        Some(row) => row,
-    };
+/// Follows the beam entering at `start` heading `direction`, returning every
+/// tile it passed through.
+///
+/// A beam that reaches a tile in a direction it has already been in there
+/// would only repeat itself, so it is dropped, which ends any loops.
+pub fn energize(
+    contraption: &Grid<Tile>,
+    start: Point,
+    direction: Direction,
+) -> Result<Grid<bool>, PuzzleError> {
+    let mut visited = contraption.map(|_| 0u8);
+    let mut beams = vec![(start, direction)];
+
+    while let Some((point, direction)) = beams.pop() {
+        let Some(tile) = contraption.get(point.x, point.y) else {
+            continue;
+        };
+        let Some(seen) = visited.get_mut(point.x, point.y) else {
+            continue;
+        };
+        let bit = direction_bit(direction)?;
+        if *seen & bit != 0 {
+            continue;
+        }
+        *seen |= bit;
 
-    let mut tile = match row.get_mut(coord.x) {
-        None => return,
-        Some(tile) => tile,
-    };
+        beams.extend(
+            tile.exits(direction)
+                .into_iter()
+                .filter_map(|exit| Some((point.checked_step(exit)?, exit))),
+        );
+    }
 
-    tile.1 = true;
+    Ok(visited.map(|seen| *seen != 0))
+}
 
-    match tile.0 {
-        Tile::Empty => {
-            if let Some(new_coord) = new_coord(coord, direction) {
-                get_energized_tiles(grid, new_coord, direction);
-            }
-        }
-        Tile::BackwardMirror => {
-            let new_direction = match direction {
-                Direction::Up => Direction::Left,
-                Direction::Down => Direction::Right,
-                Direction::Left => Direction::Up,
-                Direction::Right => Direction::Down,
-            };
-
-            if let Some(new_coord) = new_coord(coord, new_direction) {
-                get_energized_tiles(grid, new_coord, new_direction);
-            }
-        }
-        Tile::ForwardMirror => {
-            let new_direction = match direction {
-                Direction::Up => Direction::Right,
-                Direction::Down => Direction::Left,
-                Direction::Left => Direction::Down,
-                Direction::Right => Direction::Up,
-            };
-
-            if let Some(new_coord) = new_coord(coord, new_direction) {
-                get_energized_tiles(grid, new_coord, new_direction);
-            }
-        }
-        Tile::VerticalSplitter => {
-            match direction {
-                Direction::Down | Direction::Up => {
-                    if let Some(new_coord) = new_coord(coord, new_direction) {
-                        get_energized_tiles(grid, new_coord, new_direction);
-                    }
-                    if let Some(new_coord) = new_coord(coord, new_direction) {
-                        get_energized_tiles(grid, new_coord, new_direction);
-                    }
-                }
-                Direction::Left | Direction::Right => {
-                    if let Some(new_coord) = new_coord(coord, new_direction) {
-                        get_energized_tiles(grid, new_coord, new_direction);
-                    }
-                }
-            }
-            if let Some(new_coord) = new_coord(coord, new_direction) {
-                get_energized_tiles(grid, new_coord, new_direction);
-            }
-        }
-    }
+fn energized_count(
+    contraption: &Grid<Tile>,
+    start: Point,
+    direction: Direction,
+) -> Result<usize, PuzzleError> {
+    Ok(energize(contraption, start, direction)?
+        .positions(|energized| *energized)
+        .count())
 }
 
 pub struct Day16 {}
 
 impl Solvable for Day16 {
+    type Parsed = Grid<Tile>;
+
     fn get_day() -> u32 {
         16
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let grid = read_to_string(path)?
-            .lines()
-            .fold(Vec::new(), |grid, chars| {
-                let row = chars.chars().fold(Vec::new(), |row, char| {
-                    let tile = Tile::from_str(char.to_string().as_str()).unwrap();
-                    row.push((tile, false));
-                    row
-                });
-
-                grid.push(row);
-                grid
-            });
-    }
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let contraption: Grid<Tile> = input.parse()?;
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut boxes: HashMap<u32, Vec<(String, u32)>> = HashMap::new();
-
-        read_to_string(path)?
-            .lines()
-            .next()
-            .context("")?
-            .split(",")
-            .try_for_each(|chars| {
-                let operation_index = chars
-                    .chars()
-                    .position(|char| char == '=' || char == '-')
-                    .unwrap();
-
-                let label = chars.chars().take(operation_index).collect();
-
-                let hash = chars
-                    .chars()
-                    .take(operation_index)
-                    .fold(0, |hash, char| ((hash + char as u32) * 17) % 256);
-
-                let operation = chars
-                    .chars()
-                    .skip(operation_index)
-                    .take(1)
-                    .collect::<Vec<char>>()
-                    .first()
-                    .context("No operation")?
-                    .clone();
-
-                let curr_box = if let Some(curr_box) = boxes.get_mut(&hash) {
-                    curr_box
-                } else {
-                    boxes.insert(hash.clone(), Vec::new());
-                    boxes.get_mut(&hash).unwrap()
-                };
-
-                let existing_lens = curr_box
-                    .iter()
-                    .enumerate()
-                    .find(|eval_box| eval_box.1 .0 == label)
-                    .and_then(|lens| Some((lens.0, lens.1 .1)));
-
-                let new_lens_power = chars
-                    .chars()
-                    .skip(operation_index + 1)
-                    .take(1)
-                    .collect::<Vec<char>>()
-                    .first()
-                    .and_then(|power| power.to_digit(10))
-                    .context("");
-
-                if debug {
-                    println!("{}{}{:?}", label, operation, new_lens_power);
-                }
-
-                if let Some(existing_lens) = existing_lens {
-                    if let Some(lens) = curr_box.get_mut(existing_lens.0) {
-                        if operation == '=' {
-                            lens.1 = new_lens_power?;
-                        }
-
-                        if operation == '-' {
-                            curr_box.remove(existing_lens.0);
-                        }
-                    }
-                } else {
-                    if operation == '=' {
-                        curr_box.push((label, new_lens_power?));
-                    }
-                }
-
-                if curr_box.len() == 0 {
-                    boxes.remove(&hash);
-                }
-
-                // if debug {
-                //     println!("{:?}", boxes);
-                // }
-
-                Ok::<(), anyhow::Error>(())
-            })?;
-
-        if debug {
-            println!("{:?}", boxes);
+        if contraption.width() == 0 || contraption.height() == 0 {
+            bail!(PuzzleError::malformed("The contraption is empty.").at_line(1));
         }
 
-        let sum = boxes.into_iter().fold(0, |acc, curr_box| {
-            let box_pos = curr_box.0 + 1;
-            let box_power = curr_box.1.into_iter().enumerate().fold(0, |acc, lens| {
-                let lens_pos = lens.0 + 1;
-                let lens_power = lens.1 .1;
-                (lens_pos as u32 * lens_power) + acc
-            });
-            (box_pos as u32 * box_power) + acc
-        });
+        Ok(contraption)
+    }
+
+    fn solve_part_one(contraption: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let energized = energize(&contraption, Point::new(0, 0), Direction::Right)?;
+
+        trace!(
+            trace,
+            Verbose,
+            "{}",
+            energized.map(|energized| if *energized { '#' } else { '.' })
+        );
 
-        let sum = i64::try_from(sum).context("")?;
+        Ok(energized.positions(|energized| *energized).count().into())
+    }
+
+    fn solve_part_two(contraption: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let width = contraption.width();
+        let height = contraption.height();
+
+        let entries = (0..width)
+            .flat_map(|x| {
+                [
+                    (Point::new(x, 0), Direction::Down),
+                    (Point::new(x, height - 1), Direction::Up),
+                ]
+            })
+            .chain((0..height).flat_map(|y| {
+                [
+                    (Point::new(0, y), Direction::Right),
+                    (Point::new(width - 1, y), Direction::Left),
+                ]
+            }));
+
+        let max = entries
+            .map(|(start, direction)| {
+                let count = energized_count(&contraption, start, direction)?;
+                trace!(
+                    trace,
+                    Step,
+                    "{:?} heading {:?}: {}",
+                    start,
+                    direction,
+                    count
+                );
+                Ok(count)
+            })
+            .collect::<Result<Vec<usize>, PuzzleError>>()?
+            .into_iter()
+            .max()
+            .ok_or_else(|| PuzzleError::no_solution("The contraption has no edges."))?;
+
+        Ok(max.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{energize, Day16};
+    use crate::error::ErrorKind;
+    use crate::geometry::{Direction, Point};
+    use crate::testing::example_tests;
+    use crate::{PuzzleError, Solvable};
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    example_tests! {
+        Day16,
+        part_one: [(EXAMPLE, 46)],
+        part_two: [(EXAMPLE, 51)],
+    }
+
+    #[test]
+    fn rejects_empty_contraptions() {
+        for input in ["\n", "\n\n"] {
+            let err: PuzzleError = Day16::parse(input).unwrap_err().downcast().unwrap();
+            assert_eq!(err.kind, ErrorKind::Malformed);
+        }
+    }
 
-        Ok(sum)
+    #[test]
+    fn refuses_diagonal_beams() {
+        let contraption = Day16::parse(EXAMPLE).unwrap();
+        let err = energize(&contraption, Point::new(0, 0), Direction::DownRight).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Invariant);
     }
 }
@@ -1,130 +1,119 @@
-use crate::Solvable;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::Result;
+
+#[derive(Debug, Default)]
+pub struct CubeSet {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+#[derive(Debug)]
+pub struct Game {
+    pub id: u32,
+    pub hands: Vec<CubeSet>,
+}
 
 pub struct Day2 {}
 
 impl Solvable for Day2 {
+    type Parsed = Vec<Game>;
+
     fn get_day() -> u32 {
         2
     }
 
-    fn solve_part_one(_: bool) -> Result<i64> {
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| {
+            let (id, hands) = line.strip_prefix("Game ")?.split_once(": ")?;
+
+            let hands = hands
+                .split("; ")
+                .map(|hand| {
+                    hand.split(", ")
+                        .try_fold(CubeSet::default(), |mut set, color_cube| {
+                            let (number, color) = color_cube.split_once(" ")?;
+                            let number = number.parse()?;
+
+                            match color.as_str() {
+                                "red" => set.red = number,
+                                "green" => set.green = number,
+                                "blue" => set.blue = number,
+                                _ => return Err(color.error("Expected red, green or blue.")),
+                            }
+
+                            Ok(set)
+                        })
+                })
+                .collect::<Result<Vec<CubeSet>, _>>()?;
+
+            Ok(Game {
+                id: id.parse()?,
+                hands,
+            })
+        })
+    }
+
+    fn solve_part_one(games: Self::Parsed, _: &Trace) -> Result<Answer> {
         let max_red = 12;
         let max_green = 13;
         let max_blue = 14;
 
-        let path = Path::new("src/inputs/day2.txt");
-        let sum = read_to_string(path)?
-            .lines()
-            .enumerate()
-            .try_fold(0, |sum, (index, line)| {
-                let possible = line
-                    .strip_prefix(&format!("Game {}: ", index + 1))?
-                    .split_terminator("; ")
-                    .all(|hand| {
-                        hand.split_terminator(", ").all(|color_cube| {
-                            let mut iter = color_cube.split_terminator(" ");
-                            let number = if let Some(number) = iter.next() {
-                                number
-                            } else {
-                                return false;
-                            };
-                            let number: u32 = if let Ok(number) = number.parse() {
-                                number
-                            } else {
-                                return false;
-                            };
-                            let color = if let Some(color) = iter.next() {
-                                color
-                            } else {
-                                return false;
-                            };
-
-                            match color {
-                                "red" => number <= max_red,
-                                "green" => number <= max_green,
-                                "blue" => number <= max_blue,
-                                _ => false,
-                            }
-                        })
-                    });
-
-                if possible {
-                    Some(sum + index + 1)
-                } else {
-                    Some(sum)
-                }
+        let sum: u32 = games
+            .iter()
+            .filter(|game| {
+                game.hands.iter().all(|hand| {
+                    hand.red <= max_red && hand.green <= max_green && hand.blue <= max_blue
+                })
             })
-            .context("")?;
+            .map(|game| game.id)
+            .sum();
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = Path::new("src/inputs/day2.txt");
-        let sum = read_to_string(path)?
-            .lines()
-            .enumerate()
-            .try_fold(0, |sum, (index, line)| {
-                let max_colors = line
-                    .strip_prefix(&format!("Game {}: ", index + 1))?
-                    .split_terminator("; ")
-                    .try_fold((None::<u32>, None::<u32>, None::<u32>), |max_set, hand| {
-                        let set = hand.split_terminator(", ").try_fold(
-                            (None::<u32>, None::<u32>, None::<u32>),
-                            |set, color_cube| {
-                                let mut iter = color_cube.split_terminator(" ");
-                                let number = iter.next()?.parse().ok()?;
-
-                                let color = iter.next()?;
-
-                                match color {
-                                    "red" => Some((Some(number), set.1, set.2)),
-                                    "green" => Some((set.0, Some(number), set.2)),
-                                    "blue" => Some((set.0, set.1, Some(number))),
-                                    _ => None,
-                                }
-                            },
-                        )?;
-
-                        let new_max_set = [set.0, set.1, set.2]
-                            .iter()
-                            .zip([max_set.0, max_set.1, max_set.2])
-                            .map(|(color, max_color)| {
-                                if let Some(color) = color {
-                                    if let Some(max_color) = max_color {
-                                        if max_color > *color {
-                                            return Some(max_color);
-                                        }
-                                    }
-
-                                    return Some(*color);
-                                } else {
-                                    return max_color;
-                                }
-                            })
-                            .collect::<Vec<Option<u32>>>();
-
-                        Some((new_max_set[0], new_max_set[1], new_max_set[2]))
-                    })?;
-
-                if debug {
-                    println!("{:?}", max_colors);
-                }
-
-                if let (Some(max_red), Some(max_green), Some(max_blue)) = max_colors {
-                    Some(sum + (max_red * max_green * max_blue))
-                } else {
-                    None
-                }
+    fn solve_part_two(games: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let sum: u32 = games
+            .iter()
+            .map(|game| {
+                let max_colors =
+                    game.hands
+                        .iter()
+                        .fold(CubeSet::default(), |max_set, hand| CubeSet {
+                            red: max_set.red.max(hand.red),
+                            green: max_set.green.max(hand.green),
+                            blue: max_set.blue.max(hand.blue),
+                        });
+
+                trace!(trace, Step, "{:?}", max_colors);
+
+                max_colors.red * max_colors.green * max_colors.blue
             })
-            .expect("");
+            .sum();
+
+        Ok(sum.into())
+    }
+}
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+#[cfg(test)]
+mod tests {
+    use super::Day2;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    example_tests! {
+        Day2,
+        part_one: [(EXAMPLE, 8)],
+        part_two: [(EXAMPLE, 2286)],
     }
 }
@@ -1,186 +1,145 @@
-use crate::Solvable;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::Result;
 
 #[derive(Debug)]
-struct Coord {
-    row: i32,
-    col: i32,
+pub struct PartNumber {
+    pub number: u32,
+    pub digits: usize,
+    pub point: Point,
+}
+
+impl PartNumber {
+    pub fn adjacent_to(&self, point: &Point) -> bool {
+        (0..self.digits)
+            .into_iter()
+            .any(|index| Point::new(self.point.x + index, self.point.y).chebyshev(point) <= 1)
+    }
 }
 
 #[derive(Debug)]
-struct PartNumber {
-    number: u32,
-    digits: i32,
-    coord: Coord,
+pub struct Symbol {
+    pub symbol: char,
+    pub point: Point,
 }
 
-impl PartNumber {
-    pub fn adjacent_to(self: &Self, coord: &Coord) -> bool {
-        (coord.row - self.coord.row).abs() <= 1
-            && (0..self.digits)
-                .into_iter()
-                .any(|index| (coord.col - self.coord.col - index).abs() <= 1)
-    }
+#[derive(Debug)]
+pub struct Schematic {
+    pub symbols: Vec<Symbol>,
+    pub part_numbers: Vec<PartNumber>,
 }
 
 pub struct Day3 {}
 
 impl Solvable for Day3 {
+    type Parsed = Schematic;
+
     fn get_day() -> u32 {
         3
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let mut symbol_coords: Vec<Coord> = Vec::new();
-        let mut part_numbers: Vec<PartNumber> = Vec::new();
-
-        let path = Path::new("src/inputs/day3.txt");
-        read_to_string(path)?
-            .lines()
-            .enumerate()
-            .try_for_each(|(row, line)| {
-                let mut number_str = String::new();
-                let line_len = i32::try_from(line.len()).ok()?;
-                let mut coord_col = 0;
-                line.chars().enumerate().try_for_each(|(col, char)| {
-                    let col = i32::try_from(col).ok()?;
-                    let row = i32::try_from(row).ok()?;
-
-                    if char.is_numeric() {
-                        if number_str.len() == 0 {
-                            coord_col = col;
-                        }
-                        number_str.push(char);
-                        if col != line_len - 1 {
-                            return Some(());
-                        }
-                    }
-
-                    if char.is_ascii_punctuation() {
-                        if char != '.' {
-                            symbol_coords.push(Coord { row, col });
-                        }
-                    }
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let schematic: Grid<char> = input.parse()?;
 
-                    if number_str.len() > 0 {
-                        let number = number_str.parse().ok()?;
-                        let digits = i32::try_from(number_str.len()).ok()?;
-                        let coord = Coord {
-                            row,
-                            col: coord_col,
-                        };
-                        part_numbers.push(PartNumber {
-                            number,
-                            digits,
-                            coord,
-                        });
-                        number_str = String::new();
-                    }
+        let symbols = schematic
+            .cells()
+            .filter(|(_, char)| char.is_ascii_punctuation() && **char != '.')
+            .map(|((x, y), symbol)| Symbol {
+                symbol: *symbol,
+                point: Point::new(x, y),
+            })
+            .collect::<Vec<Symbol>>();
+
+        let mut part_numbers = Vec::new();
+        for (y, row) in schematic.rows().enumerate() {
+            let mut x = 0;
+            for run in row.chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit()) {
+                if run[0].is_ascii_digit() {
+                    let digits = run.iter().collect::<String>();
+                    let number = digits.parse().map_err(|_| {
+                        PuzzleError::malformed(format!("Part number {} is too large.", digits))
+                            .at_line(y + 1)
+                            .at_column(x + 1)
+                    })?;
+
+                    part_numbers.push(PartNumber {
+                        number,
+                        digits: run.len(),
+                        point: Point::new(x, y),
+                    });
+                }
+                x += run.len();
+            }
+        }
 
-                    Some(())
-                })?;
+        Ok(Schematic {
+            symbols,
+            part_numbers,
+        })
+    }
 
-                Some(())
-            })
-            .context("")?;
+    fn solve_part_one(schematic: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let Schematic {
+            symbols,
+            mut part_numbers,
+        } = schematic;
+        let symbol_points = symbols
+            .into_iter()
+            .map(|symbol| symbol.point)
+            .collect::<Vec<Point>>();
 
-        if debug {
-            println!("{:#?}", symbol_coords);
-            println!("{:#?}", part_numbers);
-        }
+        trace!(trace, Verbose, "{:#?}", symbol_points);
+        trace!(trace, Verbose, "{:#?}", part_numbers);
 
-        let sum = symbol_coords.iter().fold(0u32, |sum, symbol_coord| {
+        let sum = symbol_points.iter().fold(0u32, |sum, symbol_point| {
             let mut new_sum = sum;
 
             part_numbers.retain(|part_number| {
-                if part_number.adjacent_to(symbol_coord) {
+                if part_number.adjacent_to(symbol_point) {
                     new_sum += part_number.number;
                     return false;
                 }
 
-                part_number.coord.row >= symbol_coord.row - 1
+                part_number.point.y + 1 >= symbol_point.y
             });
 
             new_sum
         });
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let mut symbol_coords: Vec<Coord> = Vec::new();
-        let mut part_numbers: Vec<PartNumber> = Vec::new();
-
-        let path = Path::new("src/inputs/day3.txt");
-        read_to_string(path)?
-            .lines()
-            .enumerate()
-            .try_for_each(|(row, line)| {
-                let mut number_str = String::new();
-                let line_len = i32::try_from(line.len()).ok()?;
-                let mut coord_col = 0;
-                line.chars().enumerate().try_for_each(|(col, char)| {
-                    let col = i32::try_from(col).ok()?;
-                    let row = i32::try_from(row).ok()?;
-
-                    if char.is_numeric() {
-                        if number_str.len() == 0 {
-                            coord_col = col;
-                        }
-                        number_str.push(char);
-                        if col != line_len - 1 {
-                            return Some(());
-                        }
-                    }
-
-                    if char == '*' {
-                        symbol_coords.push(Coord { row, col });
-                    }
-
-                    if number_str.len() > 0 {
-                        let number = number_str.parse().ok()?;
-                        let digits = i32::try_from(number_str.len()).ok()?;
-                        let coord = Coord {
-                            row,
-                            col: coord_col,
-                        };
-                        part_numbers.push(PartNumber {
-                            number,
-                            digits,
-                            coord,
-                        });
-                        number_str = String::new();
-                    }
-
-                    Some(())
-                })?;
-
-                Some(())
-            })
-            .context("")?;
-
-        if debug {
-            println!("{:#?}", symbol_coords);
-            println!("{:#?}", part_numbers);
-        }
-
-        let sum = symbol_coords.iter().fold(0u32, |sum, symbol_coord| {
+    fn solve_part_two(schematic: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let Schematic {
+            symbols,
+            part_numbers,
+        } = schematic;
+        let symbol_points = symbols
+            .into_iter()
+            .filter(|symbol| symbol.symbol == '*')
+            .map(|symbol| symbol.point)
+            .collect::<Vec<Point>>();
+
+        trace!(trace, Verbose, "{:#?}", symbol_points);
+        trace!(trace, Verbose, "{:#?}", part_numbers);
+
+        let sum = symbol_points.iter().fold(0u32, |sum, symbol_point| {
             let mut new_sum = sum;
 
             let mut adj_part_numbers = Vec::new();
 
             for part_number in part_numbers.iter() {
-                if part_number.adjacent_to(symbol_coord) {
-                    if debug {
-                        println!(
-                            "Adj Part: {:?} ; Symbol {:?}",
-                            part_number.number, symbol_coord
-                        );
-                    }
+                if part_number.adjacent_to(symbol_point) {
+                    trace!(
+                        trace,
+                        Step,
+                        "Adj Part: {:?} ; Symbol {:?}",
+                        part_number.number,
+                        symbol_point
+                    );
                     if adj_part_numbers.len() == 2 {
                         return new_sum;
                     } else {
@@ -188,23 +147,78 @@ impl Solvable for Day3 {
                     }
                 }
 
-                if part_number.coord.row > symbol_coord.row + 1 {
+                if part_number.point.y > symbol_point.y + 1 {
                     break;
                 }
             }
 
             if adj_part_numbers.len() == 2 {
                 new_sum += adj_part_numbers.iter().product::<u32>();
-                if debug {
-                    println!("2 Adj Part Numbers: {:?}", &adj_part_numbers);
-                    println!("Product: {:?}", &adj_part_numbers.iter().product::<u32>());
-                }
+                trace!(trace, Step, "2 Adj Part Numbers: {:?}", &adj_part_numbers);
+                trace!(
+                    trace,
+                    Step,
+                    "Product: {:?}",
+                    &adj_part_numbers.iter().product::<u32>()
+                );
             }
 
             new_sum
         });
 
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        Ok(sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day3;
+    use crate::error::ErrorKind;
+    use crate::geometry::Point;
+    use crate::testing::example_tests;
+    use crate::{PuzzleError, Solvable};
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    example_tests! {
+        Day3,
+        part_one: [(EXAMPLE, 4361)],
+        part_two: [(EXAMPLE, 467835)],
+    }
+
+    #[test]
+    fn finds_numbers_at_row_ends() {
+        let schematic = Day3::parse("12.\n..*\n.34\n").unwrap();
+        let numbers = schematic
+            .part_numbers
+            .iter()
+            .map(|part_number| (part_number.number, part_number.digits, part_number.point))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            numbers,
+            [(12, 2, Point::new(0, 0)), (34, 2, Point::new(1, 2))]
+        );
+    }
+
+    #[test]
+    fn rejects_oversized_numbers() {
+        let err: PuzzleError = Day3::parse("1.*.........\n.99999999999\n")
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err.kind, ErrorKind::Malformed);
+        assert_eq!((err.line, err.column), (Some(2), Some(2)));
     }
 }
@@ -1,121 +1,117 @@
-use crate::Solvable;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use std::collections::HashMap;
-use std::fs::read_to_string;
-use std::path::Path;
+
+#[derive(Debug)]
+pub struct Card {
+    pub winning_numbers: Vec<u32>,
+    pub your_numbers: Vec<u32>,
+}
+
+impl Card {
+    pub fn wins(&self) -> usize {
+        self.winning_numbers
+            .iter()
+            .filter(|winning_number| self.your_numbers.contains(winning_number))
+            .count()
+    }
+}
 
 pub struct Day4 {}
 
 impl Solvable for Day4 {
+    type Parsed = Vec<Card>;
+
     fn get_day() -> u32 {
         4
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-        let sum = read_to_string(path)?
-            .lines()
-            .try_fold(0, |total_points, line| {
-                let number_sets = line
-                    .split_once(": ")?
-                    .1
-                    .split(" | ")
-                    .map(|numbers| {
-                        numbers
-                            .split_whitespace()
-                            .map(|number| number.parse::<u32>().unwrap_or_default())
-                            .collect()
-                    })
-                    .collect::<Vec<Vec<u32>>>();
-
-                let winning_numbers = &number_sets[0];
-                let your_numbers = &number_sets[1];
-
-                let wins = winning_numbers.iter().fold(0, |wins, winning_number| {
-                    if your_numbers.contains(winning_number) {
-                        return wins + 1;
-                    }
-                    wins
-                });
-
-                let points = if wins > 0 { 2u32.pow(wins - 1) } else { 0 };
-
-                if debug {
-                    println!("{}", line);
-                    println!("Wins - {}; Points - {}", wins, points);
-                }
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| {
+            let (_, numbers) = line.split_once(": ")?;
+            let (winning_numbers, your_numbers) = numbers.split_once(" | ")?;
 
-                Some(total_points + points)
+            Ok(Card {
+                winning_numbers: winning_numbers.numbers()?,
+                your_numbers: your_numbers.numbers()?,
             })
-            .context("Error parsing input.")?;
-
-        let sum = i64::try_from(sum)?;
-        Ok(sum)
+        })
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
+    fn solve_part_one(cards: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let sum: u32 = cards
+            .iter()
+            .map(|card| {
+                let wins = card.wins();
+                let points = if wins > 0 {
+                    2u32.pow(wins as u32 - 1)
+                } else {
+                    0
+                };
+
+                trace!(trace, Step, "{:?}", card);
+                trace!(trace, Step, "Wins - {}; Points - {}", wins, points);
 
+                points
+            })
+            .sum();
+
+        Ok(sum.into())
+    }
+
+    fn solve_part_two(scratchcards: Self::Parsed, trace: &Trace) -> Result<Answer> {
         let mut cards: HashMap<usize, u32> = HashMap::new();
 
-        read_to_string(path)?
-            .lines()
-            .enumerate()
-            .try_for_each(|(index, line)| {
-                let index = index + 1;
-                let card_count = if let Some(card) = cards.get_mut(&index) {
-                    *card += 1;
-                    card.to_owned()
+        scratchcards.iter().enumerate().for_each(|(index, card)| {
+            let index = index + 1;
+            let card_count = if let Some(card) = cards.get_mut(&index) {
+                *card += 1;
+                card.to_owned()
+            } else {
+                cards.insert(index, 1);
+                1
+            };
+
+            let wins = card.wins();
+
+            (index + 1..index + wins + 1).for_each(|index| {
+                if let Some(card) = cards.get_mut(&index) {
+                    *card += card_count;
                 } else {
-                    cards.insert(index, 1);
-                    1
-                };
-
-                let number_sets = line
-                    .split_once(": ")?
-                    .1
-                    .split(" | ")
-                    .map(|numbers| {
-                        numbers
-                            .split_whitespace()
-                            .map(|number| number.parse::<u32>().unwrap_or_default())
-                            .collect()
-                    })
-                    .collect::<Vec<Vec<u32>>>();
-
-                let winning_numbers = &number_sets[0];
-                let your_numbers = &number_sets[1];
-
-                let wins = winning_numbers.iter().fold(0, |wins, winning_number| {
-                    if your_numbers.contains(winning_number) {
-                        return wins + 1;
-                    }
-                    wins
-                });
-
-                (index + 1..index + wins + 1).into_iter().for_each(|index| {
-                    if let Some(card) = cards.get_mut(&index) {
-                        *card += card_count;
-                    } else {
-                        cards.insert(index, card_count);
-                    }
-                });
-
-                if debug {
-                    println!("Line: {}", line);
-                    println!("Cards: {:?}\n", &cards);
+                    cards.insert(index, card_count);
                 }
+            });
 
-                Some(())
-            })
-            .context("Error parsing input.")?;
+            trace!(trace, Step, "Card: {:?}", card);
+            trace!(trace, Step, "Cards: {:?}", &cards);
+        });
+
+        let sum: u32 = cards.values().sum();
 
-        let sum: u32 = cards.iter().map(|(_, val)| val).sum();
+        Ok(sum.into())
+    }
+}
 
-        let summ = i64::try_from(sum)?;
-        Ok(summ)
+#[cfg(test)]
+mod tests {
+    use super::Day4;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    example_tests! {
+        Day4,
+        part_one: [(EXAMPLE, 13)],
+        part_two: [(EXAMPLE, 30)],
     }
 }
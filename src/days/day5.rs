@@ -1,372 +1,290 @@
-use crate::Solvable;
+use crate::interval::{Interval, IntervalSet};
+use crate::parse::sections;
+use crate::piecewise::Piecewise;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::{bail, Result};
 
-#[derive(Debug)]
-struct Range {
-    source_start: i64,
-    destination_start: i64,
-    length: i64,
-}
-
-#[derive(PartialEq, Debug)]
-enum SeedRangeStatus {
-    Completed(SeedRange),
-    Uncompleted(SeedRange),
+fn no_seeds() -> PuzzleError {
+    PuzzleError::malformed("There are no seeds to plant.").at_line(1)
 }
 
-impl SeedRangeStatus {
-    pub fn extract(self: Self) -> SeedRange {
-        match self {
-            SeedRangeStatus::Completed(seed_range) => seed_range,
-            SeedRangeStatus::Uncompleted(seed_range) => seed_range,
-        }
-    }
+#[derive(Debug)]
+pub struct Range {
+    pub source_start: i64,
+    pub destination_start: i64,
+    pub length: i64,
 }
 
 impl Range {
-    pub fn map(self: &Self, input: i64) -> Option<i64> {
-        let index = input - self.destination_start;
-        if index < 0 || index > self.length {
-            return None;
-        } else {
-            return Some(self.source_start + index);
-        }
-    }
-
-    pub fn split_map(self: &Self, seed_range: SeedRange) -> Vec<SeedRangeStatus> {
-        let index_start = seed_range.start - self.source_start;
-        let start_overlap = index_start > 0 && index_start < self.length;
-
-        let index_end = seed_range.end - self.source_start;
-        let end_overlap = index_end > 0 && index_end < self.length;
-
-        let total_overlap = index_start < 0 && index_end > self.length;
-
-        if total_overlap {
-            return vec![
-                SeedRangeStatus::Uncompleted(SeedRange {
-                    start: self.source_start,
-                    end: self.source_start - 1,
-                }),
-                SeedRangeStatus::Completed(SeedRange {
-                    start: self.destination_start,
-                    end: self.destination_start + self.length - 1,
-                }),
-                SeedRangeStatus::Uncompleted(SeedRange {
-                    start: self.source_start + self.length,
-                    end: seed_range.end,
-                }),
-            ];
-        }
-
-        match (start_overlap, end_overlap) {
-            (true, true) => vec![SeedRangeStatus::Completed(SeedRange {
-                start: self.destination_start + index_start,
-                end: self.destination_start + index_end,
-            })],
-            (true, false) => vec![
-                SeedRangeStatus::Completed(SeedRange {
-                    start: self.destination_start + index_start,
-                    end: self.destination_start + self.length - 1,
-                }),
-                SeedRangeStatus::Uncompleted(SeedRange {
-                    start: self.source_start + self.length,
-                    end: seed_range.end,
-                }),
-            ],
-            (false, true) => vec![
-                SeedRangeStatus::Uncompleted(SeedRange {
-                    start: seed_range.start,
-                    end: self.source_start - 1,
-                }),
-                SeedRangeStatus::Completed(SeedRange {
-                    start: self.destination_start,
-                    end: self.destination_start + index_end,
-                }),
-            ],
-            (false, false) => vec![SeedRangeStatus::Uncompleted(seed_range)],
-        }
+    /// The source values this range covers, and how far it moves them.
+    pub fn piece(&self) -> (Interval, i64) {
+        (
+            Interval::from_length(self.source_start, self.length),
+            self.destination_start - self.source_start,
+        )
     }
 }
 
 #[derive(Debug)]
-struct Map {
-    ranges: Vec<Range>,
+pub struct Map {
+    pub source: String,
+    pub destination: String,
+    pub ranges: Vec<Range>,
 }
 
 impl Map {
-    pub fn map(self: &Self, input: i64) -> i64 {
-        self.ranges
-            .iter()
-            .find_map(|range| range.map(input))
-            .unwrap_or(input)
+    pub fn function(&self) -> Piecewise {
+        Piecewise::from_pieces(self.ranges.iter().map(Range::piece))
     }
+}
 
-    pub fn split_map(self: &Self, seed_range: SeedRange) -> Vec<SeedRange> {
-        self.ranges
-            .iter()
-            .fold(
-                vec![SeedRangeStatus::Uncompleted(seed_range)],
-                |seed_ranges, range| {
-                    let (uncompleted, mut completed): (Vec<SeedRangeStatus>, Vec<SeedRangeStatus>) =
-                        seed_ranges.into_iter().partition(|seed_range| {
-                            matches!(seed_range, SeedRangeStatus::Uncompleted(_))
-                        });
-
-                    let new_seed_ranges =
-                        uncompleted
-                            .into_iter()
-                            .fold(Vec::new(), |mut seed_ranges, seed_range| {
-                                seed_ranges.extend(range.split_map(seed_range.extract()));
-                                seed_ranges
-                            });
-
-                    completed.extend(new_seed_ranges);
-
-                    completed
-                },
-            )
-            .into_iter()
-            .map(|seed_range_status| seed_range_status.extract())
-            .collect()
-    }
+pub struct Maps {
+    pub maps: Vec<Map>,
 }
 
-struct Maps {
-    maps: Vec<Map>,
+impl Maps {
+    /// Composes the maps leading from the `from` category to the `to` one,
+    /// like `seed` to `location`, into a single function.
+    pub fn compile(&self, from: &str, to: &str) -> Result<Piecewise> {
+        let mut category = from;
+        let mut function = Piecewise::identity();
+
+        for _ in 0..=self.maps.len() {
+            if category == to {
+                return Ok(function);
+            }
+
+            let map = self
+                .maps
+                .iter()
+                .find(|map| map.source == category)
+                .ok_or_else(|| {
+                    PuzzleError::no_solution(format!("No map from {} towards {}.", category, to))
+                })?;
+
+            function = function.then(&map.function());
+            category = &map.destination;
+        }
+
+        bail!(PuzzleError::no_solution(format!(
+            "The maps from {} loop without reaching {}.",
+            from, to
+        )))
+    }
 }
 
-#[derive(Debug, PartialEq)]
-struct SeedRange {
-    start: i64,
-    end: i64,
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub maps: Maps,
 }
 
 pub struct Day5 {}
 
 impl Solvable for Day5 {
+    type Parsed = Almanac;
+
     fn get_day() -> u32 {
         5
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut maps = Maps { maps: Vec::new() };
-
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
-        let seeds = lines
-            .next()
-            .context("File empty.")?
-            .strip_prefix("seeds: ")
-            .context("No seeds.")?
-            .split_whitespace()
-            .map(|number| number.parse().map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<i64>>>()?;
-
-        if debug {
-            println!("{:?}", seeds);
-        }
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let sections = sections(input);
+        let (seeds, maps) = sections
+            .split_first()
+            .ok_or_else(|| PuzzleError::malformed("Input is empty.").at_line(1))?;
 
-        let mut curr_map = Map { ranges: Vec::new() };
-        loop {
-            if let Some(mut line) = lines.next() {
-                if line.is_empty() {
-                    if curr_map.ranges.len() > 0 {
-                        maps.maps.push(curr_map);
-                    }
-                    line = if let Some(_) = lines.next() {
-                        if let Some(line) = lines.next() {
-                            line
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
+        let seeds = seeds.single_line()?.strip_prefix("seeds:")?.numbers()?;
+
+        let maps = maps
+            .iter()
+            .map(|section| {
+                let (title, ranges) = section.split_title();
+                let (source, destination) = title.strip_suffix(" map:")?.split_once("-to-")?;
+
+                let ranges = ranges.lines(|line| {
+                    let [destination_start, source_start, length] = line.numbers()?[..] else {
+                        bail!(line.error("Expected a destination start, source start and length."));
                     };
-                    curr_map = Map { ranges: Vec::new() };
-                }
-
-                let mut range_iter = line.split_whitespace();
-                let source_start = range_iter
-                    .next()
-                    .context("No source start number.")?
-                    .parse()?;
-                let destination_start = range_iter
-                    .next()
-                    .context("No destination start number.")?
-                    .parse()?;
-                let length = range_iter.next().context("No length number.")?.parse()?;
-
-                let range = Range {
-                    source_start,
-                    destination_start,
-                    length,
-                };
-
-                curr_map.ranges.push(range);
-            } else {
-                maps.maps.push(curr_map);
-                break;
-            }
-        }
 
-        if debug {
-            println!("{:#?}", maps.maps);
-        }
+                    Ok(Range {
+                        source_start,
+                        destination_start,
+                        length,
+                    })
+                })?;
 
-        let mapped_seeds = seeds
-            .iter()
-            .map(|seed| {
-                if debug {
-                    println!("Seed Input: {}", seed);
-                }
-                maps.maps.iter().fold(*seed, |seed, map| {
-                    let mapped_seed = map.map(seed);
-                    if debug {
-                        println!("\t{}", mapped_seed);
-                    }
-                    mapped_seed
+                Ok(Map {
+                    source: source.as_str().to_string(),
+                    destination: destination.as_str().to_string(),
+                    ranges,
                 })
             })
+            .collect::<Result<Vec<Map>>>()?;
+
+        Ok(Almanac {
+            seeds,
+            maps: Maps { maps },
+        })
+    }
+
+    fn solve_part_one(almanac: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let Almanac { seeds, maps } = almanac;
+
+        let seed_to_location = maps.compile("seed", "location")?;
+
+        trace!(trace, Step, "{:?}", seeds);
+        trace!(trace, Verbose, "{}", seed_to_location);
+
+        let locations = seeds
+            .iter()
+            .map(|seed| seed_to_location.apply(*seed))
             .collect::<Vec<i64>>();
 
-        if debug {
-            println!("{:?}", &mapped_seeds);
-        }
+        trace!(trace, Step, "{:?}", &locations);
 
-        let min = *mapped_seeds.iter().min().context("Couldn't find min.")?;
+        let min = *locations.iter().min().ok_or_else(no_seeds)?;
 
-        let min = i64::try_from(min)?;
-        Ok(min)
+        Ok(min.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut maps = Maps { maps: Vec::new() };
-
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
-        let seeds = lines
-            .next()
-            .context("File empty.")?
-            .strip_prefix("seeds: ")
-            .context("No seeds.")?
-            .split_whitespace()
-            .map(|number| number.parse().map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<i64>>>()?;
-
-        let seed_ranges = seeds
-            .chunks(2)
-            .map(|slice| SeedRange {
-                start: slice[0],
-                end: slice[0] + slice[1],
-            })
-            .collect::<Vec<SeedRange>>();
+    fn solve_part_two(almanac: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let Almanac { seeds, maps } = almanac;
 
-        if debug {
-            println!("{:?}", &seed_ranges);
+        let pairs = seeds.chunks_exact(2);
+        if !pairs.remainder().is_empty() {
+            bail!(PuzzleError::malformed(
+                "Expected the seeds to be pairs of a start and a length."
+            )
+            .at_line(1));
         }
 
-        let mut curr_map = Map { ranges: Vec::new() };
-        loop {
-            if let Some(mut line) = lines.next() {
-                if line.is_empty() {
-                    if curr_map.ranges.len() > 0 {
-                        maps.maps.push(curr_map);
-                    }
-                    line = if let Some(_) = lines.next() {
-                        if let Some(line) = lines.next() {
-                            line
-                        } else {
-                            break;
-                        }
-                    } else {
-                        break;
-                    };
-                    curr_map = Map { ranges: Vec::new() };
-                }
-
-                let mut range_iter = line.split_whitespace();
-                let destination_start = range_iter
-                    .next()
-                    .context("No destination start number.")?
-                    .parse()?;
-                let source_start = range_iter
-                    .next()
-                    .context("No source start number.")?
-                    .parse()?;
-                let length = range_iter.next().context("No length number.")?.parse()?;
-
-                let range = Range {
-                    source_start,
-                    destination_start,
-                    length,
-                };
-
-                curr_map.ranges.push(range);
-            } else {
-                maps.maps.push(curr_map);
-                break;
-            }
+        let seed_ranges = pairs
+            .map(|pair| Interval::from_length(pair[0], pair[1]))
+            .collect::<IntervalSet>();
+
+        let seed_to_location = maps.compile("seed", "location")?;
+
+        trace!(trace, Step, "{:?}", &seed_ranges);
+        trace!(trace, Verbose, "{}", seed_to_location);
+
+        let locations = seed_to_location.apply_set(&seed_ranges);
+
+        trace!(trace, Step, "{:?}", &locations);
+
+        let min = locations.min().ok_or_else(no_seeds)?;
+
+        if let Some(location_to_seed) = seed_to_location.inverse() {
+            trace!(
+                trace,
+                Summary,
+                "Location {} comes from seed {}.",
+                min,
+                location_to_seed.apply(min)
+            );
         }
 
-        if debug {
-            println!("{:#?}", &maps.maps);
+        Ok(min.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day5, Map, Maps, Range};
+    use crate::error::ErrorKind;
+    use crate::testing::example_tests;
+    use crate::{PuzzleError, Solvable};
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    example_tests! {
+        Day5,
+        part_one: [(EXAMPLE, 35)],
+        part_two: [(EXAMPLE, 46)],
+    }
+
+    fn map(source: &str, destination: &str) -> Map {
+        Map {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            ranges: vec![Range {
+                source_start: 0,
+                destination_start: 1,
+                length: 1,
+            }],
         }
+    }
 
-        let mapped_seed_ranges = seed_ranges
-            .into_iter()
-            .map(|seed_range| {
-                if debug {
-                    println!("Seed Range: {:?}", &seed_range);
-                }
-                maps.maps
-                    .iter()
-                    .fold(vec![seed_range], |mapped_seed_ranges, map| {
-                        let new_mapped_seed_ranges = mapped_seed_ranges.into_iter().fold(
-                            Vec::new(),
-                            |mut new_ranges, range| {
-                                let mut split_ranges = map.split_map(range);
-                                new_ranges.append(&mut split_ranges);
-                                new_ranges
-                            },
-                        );
-                        if debug {
-                            println!("\t{:?}", new_mapped_seed_ranges);
-                        }
-                        new_mapped_seed_ranges
-                    })
-            })
-            .collect::<Vec<Vec<SeedRange>>>();
-
-        let mapped_seed_ranges =
-            mapped_seed_ranges
-                .into_iter()
-                .fold(Vec::new(), |mut new_seed_ranges, seed_ranges| {
-                    new_seed_ranges.extend(seed_ranges);
-                    new_seed_ranges
-                });
-
-        if debug {
-            println!("{:?}", &mapped_seed_ranges);
+    fn puzzle_error(err: anyhow::Error) -> PuzzleError {
+        err.downcast().expect("a PuzzleError")
+    }
+
+    #[test]
+    fn compiles_between_any_two_stages() {
+        let maps = Day5::parse(EXAMPLE).unwrap().maps;
+        let soil_to_humidity = maps.compile("soil", "humidity").unwrap();
+
+        let steps = &maps.maps[1..6];
+        assert_eq!(steps.first().unwrap().source, "soil");
+        assert_eq!(steps.last().unwrap().destination, "humidity");
+
+        for soil in 0..120 {
+            let humidity = steps
+                .iter()
+                .fold(soil, |value, map| map.function().apply(value));
+            assert_eq!(soil_to_humidity.apply(soil), humidity, "from soil {}", soil);
         }
 
-        let min = mapped_seed_ranges
-            .into_iter()
-            .map(|seed_range| seed_range.start)
-            .filter(|range_start| range_start > &0)
-            .min()
-            .context("Couldn't find min.")?;
+        assert_eq!(maps.compile("water", "water").unwrap(), Default::default());
+    }
+
+    #[test]
+    fn reports_missing_and_looping_maps() {
+        let maps = Maps {
+            maps: vec![map("a", "b"), map("b", "a")],
+        };
+
+        let err = puzzle_error(maps.compile("a", "c").unwrap_err());
+        assert_eq!(err.kind, ErrorKind::NoSolution);
+        assert_eq!(err.message, "The maps from a loop without reaching c.");
 
-        let min = i64::try_from(min)?;
-        Ok(min)
+        let err = puzzle_error(maps.compile("c", "a").unwrap_err());
+        assert_eq!(err.kind, ErrorKind::NoSolution);
+        assert_eq!(err.message, "No map from c towards a.");
     }
 }
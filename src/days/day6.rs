@@ -1,119 +1,216 @@
-use crate::Solvable;
+use crate::math::isqrt;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Level, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::{bail, Result};
+
+fn no_races() -> PuzzleError {
+    PuzzleError::malformed("There are no races.")
+}
+
+/// The longest race `--trace verbose` also counts by trying every hold time.
+const ENUMERATION_LIMIT: u128 = 100_000;
 
 #[derive(Debug)]
-struct RaceRecord {
-    time: u64,
-    distance: u64,
+pub struct RaceRecord {
+    pub time: u128,
+    pub distance: u128,
 }
 
 impl RaceRecord {
-    pub fn margin_of_error(self: &Self) -> usize {
-        (1..self.time)
-            .into_iter()
-            .map(|speed| speed * (self.time - speed))
-            .filter(|distance| distance > &self.distance)
-            .collect::<Vec<u64>>()
-            .len()
+    fn wins(&self, speed: u128) -> bool {
+        speed * (self.time - speed) > self.distance
     }
 
-    pub fn margin_of_error_efficient(self: &Self) -> u64 {
-        let start_index = (1..self.time)
-            .into_iter()
-            .find(|speed| {
-                let distance = speed * (self.time - speed);
-                distance > self.distance
-            })
-            .unwrap_or_default();
+    /// Counts the winning hold times by trying every one of them.
+    pub fn margin_of_error_enumerated(&self) -> u128 {
+        (1..self.time).filter(|speed| self.wins(*speed)).count() as u128
+    }
+
+    /// Counts the winning hold times from the roots of
+    /// `speed * (time - speed) = distance`.
+    ///
+    /// The integer square root can land one off the first winning speed, so
+    /// it is nudged onto it with exact checks. Winning speeds are symmetric
+    /// around `time / 2`, which gives the last one.
+    pub fn margin_of_error(&self) -> Result<u128> {
+        let time_squared = self.time.checked_mul(self.time).ok_or_else(|| {
+            PuzzleError::invariant(format!("Race time {} is too long to square.", self.time))
+        })?;
+        let Some(discriminant) = self
+            .distance
+            .checked_mul(4)
+            .and_then(|distance| time_squared.checked_sub(distance))
+        else {
+            return Ok(0);
+        };
+
+        let half = self.time / 2;
+        let mut first = ((self.time - isqrt(discriminant)) / 2).min(half);
+        while first > 0 && self.wins(first - 1) {
+            first -= 1;
+        }
+        while first <= half && !self.wins(first) {
+            first += 1;
+        }
+
+        if first > half {
+            return Ok(0);
+        }
 
-        self.time - (start_index * 2) + 1
+        Ok(self.time - first * 2 + 1)
+    }
+
+    /// The closed form answer. At the verbose trace level, races up to
+    /// `ENUMERATION_LIMIT` long are cross-checked by trying every hold time,
+    /// and a disagreement is an invariant error rather than a wrong answer.
+    pub fn traced_margin_of_error(&self, trace: &Trace) -> Result<u128> {
+        let margin = self.margin_of_error()?;
+
+        if trace.enabled(Level::Verbose) && self.time <= ENUMERATION_LIMIT {
+            let enumerated = self.margin_of_error_enumerated();
+            if enumerated != margin {
+                bail!(PuzzleError::invariant(format!(
+                    "Closed form found {} ways to win {:?}, enumeration found {}.",
+                    margin, self, enumerated
+                )));
+            }
+            trace!(
+                trace,
+                Verbose,
+                "Cross-checked {:?} by enumeration: {}",
+                self,
+                margin
+            );
+        }
+
+        Ok(margin)
     }
 }
 
 pub struct Day6 {}
 
 impl Solvable for Day6 {
+    type Parsed = Vec<RaceRecord>;
+
     fn get_day() -> u32 {
         6
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let lines = parse_lines(input, |line| Ok(line.key_values::<u128>()?.1))?;
 
-        let mut times = lines.next().context("")?.split_whitespace();
-        times.next();
-        let times = times
-            .map(|time| time.parse().map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<u64>>>()?;
-
-        let mut distances = lines.next().context("")?.split_whitespace();
-        distances.next();
-        let distances = distances
-            .map(|time| time.parse().map_err(anyhow::Error::msg))
-            .collect::<Result<Vec<u64>>>()?;
+        let [times, distances] = <[Vec<u128>; 2]>::try_from(lines).map_err(|lines| {
+            PuzzleError::malformed(format!("Expected 2 lines, found {}.", lines.len()))
+        })?;
+        if times.len() != distances.len() {
+            bail!(PuzzleError::malformed(
+                "Every race needs both a time and a distance."
+            ));
+        }
+        if times.is_empty() {
+            bail!(no_races());
+        }
 
-        let race_records = times
+        Ok(times
             .into_iter()
             .zip(distances)
             .map(|(time, distance)| RaceRecord { time, distance })
-            .collect::<Vec<RaceRecord>>();
+            .collect())
+    }
 
-        if debug {
-            println!("{:#?}", &race_records);
-        }
+    fn solve_part_one(race_records: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:#?}", &race_records);
 
         let margin_product = race_records
             .into_iter()
-            .map(|race_record| race_record.margin_of_error())
+            .map(|race_record| race_record.traced_margin_of_error(trace))
+            .collect::<Result<Vec<u128>>>()?
+            .into_iter()
             .fold(None, |product, margin| match product {
                 Some(product) => Some(product * margin),
                 None => Some(margin),
             })
-            .context("Mult failed.")?;
+            .ok_or_else(no_races)?;
 
-        let margin_product = i64::try_from(margin_product)?;
-        Ok(margin_product)
+        Ok(margin_product.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
-
-        let mut time = lines.next().context("")?.split_whitespace();
-        time.next();
-        let time: u64 = time
-            .fold(String::new(), |combined_string, number| {
-                combined_string + number
+    fn solve_part_two(race_records: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        // The kerning was a lie, every column is one digit of a single race.
+        let time: u128 = race_records
+            .iter()
+            .fold(String::new(), |combined_string, race_record| {
+                combined_string + &race_record.time.to_string()
             })
             .parse()?;
 
-        let mut distance = lines.next().context("")?.split_whitespace();
-        distance.next();
-        let distance: u64 = distance
-            .fold(String::new(), |combined_string, number| {
-                combined_string + number
+        let distance: u128 = race_records
+            .iter()
+            .fold(String::new(), |combined_string, race_record| {
+                combined_string + &race_record.distance.to_string()
             })
             .parse()?;
 
         let race_record = RaceRecord { time, distance };
 
-        if debug {
-            println!("{:#?}", &race_record);
+        trace!(trace, Verbose, "{:#?}", &race_record);
+
+        let margin_of_error = race_record.traced_margin_of_error(trace)?;
+
+        Ok(margin_of_error.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day6, RaceRecord};
+    use crate::testing::example_tests;
+    use crate::trace::Level;
+    use crate::{Part, Tracer};
+
+    const EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+    const LONG_RACE: &str = "\
+Time:      71530  123456789
+Distance:  940200  123456789012345
+";
+
+    example_tests! {
+        Day6,
+        part_one: [(EXAMPLE, 288)],
+        part_two: [(EXAMPLE, 71503), (LONG_RACE, 71530097168556_u64)],
+    }
+
+    #[test]
+    fn closed_form_matches_enumeration() {
+        for time in 0..200 {
+            for distance in 0..time * time / 4 + 2 {
+                let race_record = RaceRecord { time, distance };
+                assert_eq!(
+                    race_record.margin_of_error().unwrap(),
+                    race_record.margin_of_error_enumerated(),
+                    "{:?}",
+                    race_record
+                );
+            }
         }
+    }
 
-        let margin_of_error = race_record.margin_of_error_efficient();
+    #[test]
+    fn cross_checks_at_the_verbose_level() {
+        let race_record = RaceRecord {
+            time: 71530,
+            distance: 940200,
+        };
 
-        let margin_of_error = i64::try_from(margin_of_error)?;
-        Ok(margin_of_error)
+        for tracer in [Tracer::default(), Tracer::new(Level::Verbose)] {
+            let trace = tracer.for_part(6, Part::Two);
+            assert_eq!(race_record.traced_margin_of_error(&trace).unwrap(), 71503);
+        }
     }
 }
@@ -1,16 +1,14 @@
-use crate::Solvable;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
-use std::str::FromStr;
+use anyhow::Result;
 use strum_macros::EnumString;
 
-use std::cmp::Ordering;
 use std::collections::HashMap;
 
 #[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Hash, EnumString, Debug)]
-enum Card {
+pub enum Card {
     #[strum(serialize = "A")]
     Ace,
     #[strum(serialize = "K")]
@@ -41,298 +39,288 @@ enum Card {
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Hand {
-    hand: Vec<Card>,
-    bid: usize,
-    hand_type: Option<HandType>,
+    pub hand: Vec<Card>,
+    pub bid: usize,
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let ordering = self.hand_type.partial_cmp(&other.hand_type);
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum HandType {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
 
-        if let Some(Ordering::Equal) = ordering {
-            self.hand
-                .iter()
-                .zip(other.hand.iter())
-                .find_map(|(self_card, other_card)| {
-                    let ordering = self_card.partial_cmp(&other_card);
-                    if let Some(Ordering::Equal) = ordering {
-                        None
-                    } else {
-                        ordering
-                    }
-                })
-        } else {
-            ordering
+impl HandType {
+    /// Classifies a hand from how many of each card it has, largest first.
+    pub fn from_counts(counts: &[usize]) -> Self {
+        let first = counts.first().copied().unwrap_or_default();
+        let second = counts.get(1).copied().unwrap_or_default();
+
+        match (first, second) {
+            (5.., _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2..) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2..) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
         }
     }
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
-    }
+/// How hands are played: which cards beat which, which stand in for any
+/// other card, and how many cards make a hand.
+#[derive(Debug)]
+pub struct Rules {
+    /// Every card, strongest first.
+    pub order: Vec<Card>,
+    pub wild: Vec<Card>,
+    pub hand_size: usize,
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Debug)]
-enum HandType {
-    FiveOfAKind,
-    FourOfAKind,
-    FullHouse,
-    ThreeOfAKind,
-    TwoPair,
-    OnePair,
-    HighCard,
-}
+impl Rules {
+    pub fn standard() -> Self {
+        Rules {
+            order: vec![
+                Card::Ace,
+                Card::King,
+                Card::Queen,
+                Card::Jack,
+                Card::Ten,
+                Card::Nine,
+                Card::Eight,
+                Card::Seven,
+                Card::Six,
+                Card::Five,
+                Card::Four,
+                Card::Three,
+                Card::Two,
+            ],
+            wild: Vec::new(),
+            hand_size: 5,
+        }
+    }
+
+    /// Jacks become jokers, which are wild but weaker than any other card.
+    pub fn jokers() -> Self {
+        let mut rules = Rules::standard();
+        rules.order.retain(|card| *card != Card::Jack);
+        rules.order.push(Card::Jack);
+        rules.wild = vec![Card::Jack];
+        rules
+    }
 
-impl Hand {
-    fn hand_type(self: &Self) -> HandType {
-        let mut card_counts: HashMap<Card, u32> = HashMap::new();
+    fn strength(&self, card: Card) -> Result<usize, PuzzleError> {
+        self.order
+            .iter()
+            .rev()
+            .position(|ranked| *ranked == card)
+            .ok_or_else(|| PuzzleError::invariant(format!("{:?} has no place in the rules.", card)))
+    }
 
-        self.hand.iter().for_each(|card| {
-            if card_counts.contains_key(card) {
-                let prev_val = card_counts.get(&card).unwrap_or(&0);
-                card_counts.insert(card.clone(), prev_val + 1);
+    /// Wild cards join whichever group of cards is already largest.
+    pub fn hand_type(&self, hand: &[Card]) -> HandType {
+        let mut card_counts: HashMap<Card, usize> = HashMap::new();
+        let mut wild_count = 0;
+
+        for card in hand {
+            if self.wild.contains(card) {
+                wild_count += 1;
             } else {
-                card_counts.insert(card.clone(), 1);
+                *card_counts.entry(*card).or_default() += 1;
             }
-        });
-
-        let mut card_counts = card_counts.into_values().collect::<Vec<u32>>();
-        card_counts.sort();
-        card_counts.reverse();
-
-        match card_counts.len() {
-            1 => HandType::FiveOfAKind,
-            2 if card_counts[0] == 4 => HandType::FourOfAKind,
-            2 => HandType::FullHouse,
-            3 if card_counts[0] == 3 => HandType::ThreeOfAKind,
-            3 => HandType::TwoPair,
-            4 => HandType::OnePair,
-            5 => HandType::HighCard,
-            _ => panic!(),
         }
-    }
-}
 
-#[derive(PartialEq, PartialOrd, Eq, Copy, Clone, Hash, EnumString, Debug)]
-enum CardJokers {
-    #[strum(serialize = "A")]
-    Ace,
-    #[strum(serialize = "K")]
-    King,
-    #[strum(serialize = "Q")]
-    Queen,
-    #[strum(serialize = "T")]
-    Ten,
-    #[strum(serialize = "9")]
-    Nine,
-    #[strum(serialize = "8")]
-    Eight,
-    #[strum(serialize = "7")]
-    Seven,
-    #[strum(serialize = "6")]
-    Six,
-    #[strum(serialize = "5")]
-    Five,
-    #[strum(serialize = "4")]
-    Four,
-    #[strum(serialize = "3")]
-    Three,
-    #[strum(serialize = "2")]
-    Two,
-    #[strum(serialize = "J")]
-    Joker,
-}
+        let mut card_counts = card_counts.into_values().collect::<Vec<usize>>();
+        card_counts.sort_by(|a, b| b.cmp(a));
 
-#[derive(PartialEq, Eq, Debug)]
-pub struct HandJokers {
-    hand: Vec<CardJokers>,
-    bid: usize,
-    hand_type: Option<HandType>,
-}
+        match card_counts.first_mut() {
+            Some(largest) => *largest += wild_count,
+            None => card_counts.push(wild_count),
+        }
 
-impl PartialOrd for HandJokers {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let ordering = self.hand_type.partial_cmp(&other.hand_type);
+        HandType::from_counts(&card_counts)
+    }
 
-        if let Some(Ordering::Equal) = ordering {
-            self.hand
-                .iter()
-                .zip(other.hand.iter())
-                .find_map(|(self_card, other_card)| {
-                    let ordering = self_card.partial_cmp(&other_card);
-                    if let Some(Ordering::Equal) = ordering {
-                        None
-                    } else {
-                        ordering
-                    }
-                })
-        } else {
-            ordering
+    pub fn rank(&self, hand: &Hand) -> Result<Rank, PuzzleError> {
+        if hand.hand.len() != self.hand_size {
+            return Err(PuzzleError::malformed(format!(
+                "Expected a hand of {} cards, found {}.",
+                self.hand_size,
+                hand.hand.len()
+            )));
         }
-    }
-}
 
-impl Ord for HandJokers {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+        Ok(Rank {
+            hand_type: self.hand_type(&hand.hand),
+            strengths: hand
+                .hand
+                .iter()
+                .map(|card| self.strength(*card))
+                .collect::<Result<Vec<usize>, PuzzleError>>()?,
+        })
     }
-}
 
-impl HandJokers {
-    fn hand_type(self: &Self) -> HandType {
-        let mut card_counts: HashMap<CardJokers, u32> = HashMap::new();
+    /// Every bid multiplied by how its hand ranks, weakest first.
+    pub fn total_winnings(&self, hands: &[Hand], trace: &Trace) -> Result<usize> {
+        let mut ranked = hands
+            .iter()
+            .map(|hand| Ok((self.rank(hand)?, hand.bid)))
+            .collect::<Result<Vec<(Rank, usize)>>>()?;
 
-        self.hand.iter().for_each(|card| {
-            if card_counts.contains_key(card) {
-                let prev_val = card_counts.get(&card).unwrap_or(&0);
-                card_counts.insert(card.clone(), prev_val + 1);
-            } else {
-                card_counts.insert(card.clone(), 1);
-            }
-        });
-
-        let jokers_count = if card_counts.len() != 1 || !card_counts.contains_key(&CardJokers::Joker)
-        {
-            card_counts
-                .remove_entry(&CardJokers::Joker)
-                .and_then(|entry| Some(entry.1))
-                .unwrap_or_default()
-        } else {
-            0
-        };
+        ranked.sort();
 
-        let mut card_counts = card_counts.into_values().collect::<Vec<u32>>();
-        card_counts.sort();
-        card_counts.reverse();
-
-        card_counts[0] += jokers_count;
-
-        match card_counts.len() {
-            1 => HandType::FiveOfAKind,
-            2 if card_counts[0] == 4 => HandType::FourOfAKind,
-            2 => HandType::FullHouse,
-            3 if card_counts[0] == 3 => HandType::ThreeOfAKind,
-            3 => HandType::TwoPair,
-            4 => HandType::OnePair,
-            5 => HandType::HighCard,
-            _ => panic!(),
-        }
+        trace!(trace, Verbose, "{:#?}", &ranked);
+
+        Ok(ranked
+            .iter()
+            .enumerate()
+            .fold(0, |total_winnings, (index, (_, bid))| {
+                total_winnings + ((index + 1) * bid)
+            }))
     }
 }
 
+/// What hands are ordered by: their type, then each card in turn.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Rank {
+    pub hand_type: HandType,
+    pub strengths: Vec<usize>,
+}
+
 pub struct Day7 {}
 
 impl Solvable for Day7 {
+    type Parsed = Vec<Hand>;
+
     fn get_day() -> u32 {
         7
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut hands = read_to_string(path)?
-            .lines()
-            .map(|line| {
-                (|| {
-                    let mut line_tokens = line.split_whitespace();
-                    let hand = line_tokens.next().context("")?;
-                    let hand = hand
-                        .chars()
-                        .map(|card| {
-                            Card::from_str(card.to_string().as_str()).map_err(anyhow::Error::msg)
-                        })
-                        .collect::<Result<Vec<Card>>>()?;
-
-                    let bid = line_tokens.next().context("")?.parse()?;
-
-                    let mut hand = Hand {
-                        hand,
-                        bid,
-                        hand_type: None,
-                    };
-                    hand.hand_type = Some(hand.hand_type());
-
-                    Ok(hand)
-                })()
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| {
+            let (hand, bid) = line.split_once(" ")?;
+
+            Ok(Hand {
+                hand: hand.cells()?,
+                bid: bid.parse()?,
             })
-            .collect::<Result<Vec<Hand>>>()?;
+        })
+    }
 
-        if debug {
-            println!("{:#?}", &hands);
-        }
+    fn solve_part_one(hands: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:#?}", &hands);
 
-        hands.sort();
+        Ok(Rules::standard().total_winnings(&hands, trace)?.into())
+    }
 
-        if debug {
-            println!("{:#?}", &hands);
-        }
+    fn solve_part_two(hands: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:#?}", &hands);
 
-        let total_winnings = hands
-            .iter()
-            .rev()
-            .enumerate()
-            .fold(0, |total_winnings, (index, hand)| {
-                total_winnings + ((index + 1) * hand.bid)
-            });
+        Ok(Rules::jokers().total_winnings(&hands, trace)?.into())
+    }
+}
 
-        let total_winnings = i64::try_from(total_winnings)?;
-        Ok(total_winnings)
+#[cfg(test)]
+mod tests {
+    use super::{Card, Day7, Hand, HandType, Rules};
+    use crate::error::ErrorKind;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    example_tests! {
+        Day7,
+        part_one: [(EXAMPLE, 6440)],
+        part_two: [(EXAMPLE, 5905)],
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let mut hands = read_to_string(path)?
-            .lines()
-            .map(|line| {
-                (|| {
-                    let mut line_tokens = line.split_whitespace();
-                    let hand = line_tokens.next().context("")?;
-                    let hand = hand
-                        .chars()
-                        .map(|card| {
-                            CardJokers::from_str(card.to_string().as_str())
-                                .map_err(anyhow::Error::msg)
-                        })
-                        .collect::<Result<Vec<CardJokers>>>()?;
-
-                    let bid = line_tokens.next().context("")?.parse()?;
-
-                    let mut hand = HandJokers {
-                        hand,
-                        bid,
-                        hand_type: None,
-                    };
-                    hand.hand_type = Some(hand.hand_type());
-
-                    Ok(hand)
-                })()
-            })
-            .collect::<Result<Vec<HandJokers>>>()?;
+    fn cards(hand: &str) -> Vec<Card> {
+        hand.chars()
+            .map(|card| card.to_string().parse().unwrap())
+            .collect()
+    }
 
-        if debug {
-            println!("{:#?}", &hands);
+    #[test]
+    fn classifies_counts() {
+        let cases = [
+            (&[5][..], HandType::FiveOfAKind),
+            (&[4, 1], HandType::FourOfAKind),
+            (&[3, 2], HandType::FullHouse),
+            (&[3, 1, 1], HandType::ThreeOfAKind),
+            (&[2, 2, 1], HandType::TwoPair),
+            (&[2, 1, 1, 1], HandType::OnePair),
+            (&[1, 1, 1, 1, 1], HandType::HighCard),
+            (&[6, 1], HandType::FiveOfAKind),
+            (&[3, 3], HandType::FullHouse),
+            (&[], HandType::HighCard),
+        ];
+
+        for (counts, expected) in cases {
+            assert_eq!(HandType::from_counts(counts), expected, "{:?}", counts);
         }
+    }
 
-        hands.sort();
+    #[test]
+    fn wild_cards_join_the_largest_group() {
+        let rules = Rules {
+            wild: vec![Card::Jack, Card::Two],
+            ..Rules::standard()
+        };
 
-        if debug {
-            println!("{:#?}", &hands);
-        }
+        assert_eq!(rules.hand_type(&cards("AKQT9")), HandType::HighCard);
+        assert_eq!(rules.hand_type(&cards("AKQJ9")), HandType::OnePair);
+        assert_eq!(rules.hand_type(&cards("AKQJ2")), HandType::ThreeOfAKind);
+        assert_eq!(rules.hand_type(&cards("AAKJ2")), HandType::FourOfAKind);
+        assert_eq!(rules.hand_type(&cards("AAKKJ")), HandType::FullHouse);
+        assert_eq!(rules.hand_type(&cards("AJ2J2")), HandType::FiveOfAKind);
+        assert_eq!(rules.hand_type(&cards("JJ2J2")), HandType::FiveOfAKind);
+        assert_eq!(
+            Rules::jokers().hand_type(&cards("JJJJJ")),
+            HandType::FiveOfAKind
+        );
+        assert_eq!(
+            Rules::standard().hand_type(&cards("AKQJ2")),
+            HandType::HighCard
+        );
+    }
 
-        let total_winnings = hands
-            .iter()
-            .rev()
-            .enumerate()
-            .fold(0, |total_winnings, (index, hand)| {
-                total_winnings + ((index + 1) * hand.bid)
-            });
+    #[test]
+    fn ranks_hands_of_other_sizes() {
+        let rules = Rules {
+            hand_size: 3,
+            ..Rules::jokers()
+        };
 
-        let total_winnings = i64::try_from(total_winnings)?;
-        Ok(total_winnings)
+        let weak = Hand {
+            hand: cards("J23"),
+            bid: 1,
+        };
+        let strong = Hand {
+            hand: cards("22J"),
+            bid: 1,
+        };
+        assert_eq!(rules.hand_type(&weak.hand), HandType::OnePair);
+        assert_eq!(rules.hand_type(&strong.hand), HandType::ThreeOfAKind);
+        assert!(rules.rank(&weak).unwrap() < rules.rank(&strong).unwrap());
+
+        let err = rules
+            .rank(&Hand {
+                hand: cards("AKQT9"),
+                bid: 1,
+            })
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Malformed);
+        assert_eq!(err.message, "Expected a hand of 3 cards, found 5.");
     }
 }
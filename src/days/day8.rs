@@ -1,65 +1,205 @@
-use crate::Solvable;
+use crate::cycle;
+use crate::math::{crt, lcm};
+use crate::parse::sections;
+use crate::trace::{trace, Trace};
+use crate::{Answer, PuzzleError, Solvable};
 
-use anyhow::{Context, Result};
-use std::fs::read_to_string;
-use std::path::Path;
+use anyhow::{bail, Result};
 
 use std::collections::HashMap;
 
-fn gcd(mut a: u64, mut b: u64) -> u64 {
-    while b != 0 {
-        (a, b) = (b, a % b);
+fn no_instructions() -> PuzzleError {
+    PuzzleError::invariant("Instructions are empty.")
+}
+
+fn missing_node(key: &str) -> PuzzleError {
+    PuzzleError::malformed(format!("Node \"{}\" is not in the map.", key))
+}
+
+#[derive(Debug)]
+pub struct Network {
+    pub instructions: String,
+    pub map: HashMap<String, (String, String)>,
+}
+
+impl Network {
+    /// Moves from `node` following the instruction at `index`, returning
+    /// the next node and the index of the instruction after.
+    pub fn step<'a>(&'a self, node: &str, index: usize) -> Result<(&'a str, usize)> {
+        let direction = self
+            .instructions
+            .as_bytes()
+            .get(index)
+            .ok_or_else(no_instructions)?;
+        let (left, right) = self.map.get(node).ok_or_else(|| missing_node(node))?;
+
+        let node = if *direction == b'L' { left } else { right };
+        Ok((node, (index + 1) % self.instructions.len()))
+    }
+}
+
+/// One ghost's walk, reduced to where it starts repeating and the steps it
+/// ends on a `Z` node.
+#[derive(Debug)]
+pub struct Ghost {
+    /// Steps taken before the walk first reaches its cycle.
+    pub start: u64,
+    pub period: u64,
+    /// Steps before the cycle that end on a `Z` node.
+    pub prefix_hits: Vec<u64>,
+    /// Steps in the first pass of the cycle that end on a `Z` node, each
+    /// happening again every `period` steps.
+    pub cycle_hits: Vec<u64>,
+}
+
+impl Ghost {
+    /// Walks from `start` until a node is reached at the same instruction
+    /// twice, which is where the walk starts repeating.
+    pub fn follow(network: &Network, start: &str) -> Result<Self> {
+        let history = cycle::find((start, 0), |&(node, index)| network.step(node, index))?;
+        let cycle_start = history.cycle.start as u64;
+
+        let (prefix_hits, cycle_hits) = history
+            .states
+            .iter()
+            .enumerate()
+            .filter(|(_, (node, _))| node.ends_with('Z'))
+            .map(|(step, _)| step as u64)
+            .partition(|step| *step < cycle_start);
+
+        Ok(Ghost {
+            start: cycle_start,
+            period: history.cycle.period as u64,
+            prefix_hits,
+            cycle_hits,
+        })
+    }
+
+    pub fn is_hit(&self, step: u64) -> bool {
+        if step < self.start {
+            self.prefix_hits.contains(&step)
+        } else {
+            self.cycle_hits
+                .iter()
+                .any(|hit| step % self.period == hit % self.period)
+        }
     }
 
-    a
+    /// Whether the ghost reaches a `Z` node for the first time after exactly
+    /// one period and then only once a period, which is what makes the
+    /// least common multiple of the periods the answer.
+    pub fn is_aligned(&self) -> bool {
+        self.prefix_hits.is_empty() && self.cycle_hits == [self.period]
+    }
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    (a * b) / gcd(a, b)
+/// The first step on which every ghost is on a `Z` node at once.
+///
+/// Steps before every ghost is in its cycle are checked one by one. After
+/// that each ghost needs the step to match one of its hits modulo its
+/// period, which the Chinese remainder theorem solves.
+pub fn first_common_hit(ghosts: &[Ghost]) -> Result<u64, PuzzleError> {
+    let never = || PuzzleError::no_solution("The ghosts are never all on a Z node at once.");
+
+    let start = ghosts
+        .iter()
+        .map(|ghost| ghost.start)
+        .max()
+        .ok_or_else(never)?;
+
+    if let Some(step) = (0..start).find(|step| ghosts.iter().all(|ghost| ghost.is_hit(*step))) {
+        return Ok(step);
+    }
+
+    // The first step from `start` on is worked out in u128, as it can be
+    // past the end of a u64 even when the residue and modulus aren't.
+    let step = ghosts
+        .iter()
+        .fold(vec![(0, 1)], |congruences, ghost| {
+            congruences
+                .iter()
+                .flat_map(|congruence| {
+                    ghost
+                        .cycle_hits
+                        .iter()
+                        .filter_map(|hit| crt([*congruence, (hit % ghost.period, ghost.period)]))
+                })
+                .collect()
+        })
+        .into_iter()
+        .map(|(residue, modulus)| {
+            let (start, residue, modulus) = (start as u128, residue as u128, modulus as u128);
+            start + (residue + modulus - start % modulus) % modulus
+        })
+        .min()
+        .ok_or_else(never)?;
+
+    u64::try_from(step).map_err(|_| {
+        PuzzleError::no_solution(format!(
+            "The ghosts first meet on step {}, which is too far to count.",
+            step
+        ))
+    })
 }
 
 pub struct Day8 {}
 
 impl Solvable for Day8 {
+    type Parsed = Network;
+
     fn get_day() -> u32 {
         8
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
-        let instructions = lines.next().context("No instructions")?.to_owned();
-
-        lines.next().context("")?;
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        let [instructions, nodes] = sections(input)[..] else {
+            bail!(PuzzleError::malformed(
+                "Expected instructions and nodes separated by a blank line."
+            ));
+        };
+
+        let instructions = instructions.single_line()?;
+        if let Some(direction) = instructions
+            .chars()
+            .find(|direction| !matches!(direction.as_str(), "L" | "R"))
+        {
+            bail!(direction.error("Instructions can only be L or R."));
+        }
 
-        let map = lines
-            .try_fold(HashMap::new(), |mut map, line| {
-                let (key, value) = line.split_once(" = (")?;
-                let value = value.trim_end_matches(")");
-                let value = value.split_once(", ")?;
-                let value = (value.0.to_owned(), value.1.to_owned());
+        let map = nodes
+            .lines(|line| {
+                let (key, value) = line.split_once(" = ")?;
+                let (left, right) = value
+                    .strip_prefix("(")?
+                    .strip_suffix(")")?
+                    .split_once(", ")?;
+
+                Ok((
+                    key.as_str().to_owned(),
+                    (left.as_str().to_owned(), right.as_str().to_owned()),
+                ))
+            })?
+            .into_iter()
+            .collect::<HashMap<String, (String, String)>>();
+        let instructions = instructions.as_str().to_owned();
 
-                map.insert(key.to_owned(), value);
+        Ok(Network { instructions, map })
+    }
 
-                Some(map)
-            })
-            .context("Could not parse map")?;
+    fn solve_part_one(network: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let Network { instructions, map } = network;
 
-        if debug {
-            println!("{:#?}", instructions);
-            println!("{:#?}", map);
-        }
+        trace!(trace, Verbose, "{:#?}", instructions);
+        trace!(trace, Verbose, "{:#?}", map);
 
         let mut key = "AAA";
         let mut instructions_iter = instructions.chars();
-        let mut direction = instructions_iter.next().context("")?;
+        let mut direction = instructions_iter.next().ok_or_else(no_instructions)?;
 
-        let mut step_count = 0;
+        let mut step_count = 0u64;
 
         loop {
-            let value = map.get(key).context("Map does not contain key")?;
+            let value = map.get(key).ok_or_else(|| missing_node(key))?;
             if direction == 'L' {
                 key = &value.0;
             } else {
@@ -76,100 +216,144 @@ impl Solvable for Day8 {
                 direction
             } else {
                 instructions_iter = instructions.chars();
-                instructions_iter.next().context("")?
+                instructions_iter.next().ok_or_else(no_instructions)?
             }
         }
 
-        Ok(step_count)
+        Ok(step_count.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-        let file_string = read_to_string(path)?;
-        let mut lines = file_string.lines();
-        let instructions = lines.next().context("No instructions")?.to_owned();
+    fn solve_part_two(network: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        let starts = network
+            .map
+            .keys()
+            .filter(|key| key.ends_with('A'))
+            .collect::<Vec<&String>>();
 
-        lines.next().context("")?;
+        trace!(trace, Verbose, "{:#?}", starts);
 
-        let map = lines
-            .try_fold(HashMap::new(), |mut map, line| {
-                let (key, value) = line.split_once(" = (")?;
-                let value = value.trim_end_matches(")");
-                let value = value.split_once(", ")?;
-                let value = (value.0.to_owned(), value.1.to_owned());
+        let ghosts = starts
+            .into_iter()
+            .map(|start| Ghost::follow(&network, start))
+            .collect::<Result<Vec<Ghost>>>()?;
 
-                map.insert(key.to_owned(), value);
+        trace!(trace, Verbose, "{:#?}", ghosts);
 
-                Some(map)
-            })
-            .context("Could not parse map")?;
+        if ghosts.is_empty() {
+            bail!(PuzzleError::no_solution("No nodes end with A."));
+        }
 
-        // if debug {
-        //     println!("{:#?}", instructions);
-        //     println!("{:#?}", map);
-        // }
+        let step_count = if ghosts.iter().all(Ghost::is_aligned) {
+            trace!(
+                trace,
+                Summary,
+                "Every ghost reaches Z once a cycle, one cycle in, so the LCM of the cycles holds."
+            );
+
+            ghosts
+                .iter()
+                .fold(1, |step_count, ghost| lcm(step_count, ghost.period))
+        } else {
+            trace!(
+                trace,
+                Summary,
+                "The ghosts' Z nodes aren't aligned with their cycles, solving with CRT instead."
+            );
+
+            first_common_hit(&ghosts)?
+        };
+
+        Ok(step_count.into())
+    }
+}
 
-        let keys = map.keys().fold(Vec::new(), |mut keys, key| {
-            if key.ends_with("A") {
-                keys.push(key);
-            }
-            keys
-        });
+#[cfg(test)]
+mod tests {
+    use super::{first_common_hit, Day8, Ghost};
+    use crate::error::ErrorKind;
+    use crate::testing::example_tests;
+
+    const EXAMPLE_ONE: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_TWO: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+
+    const EXAMPLE_THREE: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    const EXAMPLE_UNALIGNED: &str = "\
+L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22C, 22C)
+22C = (22D, 22D)
+22D = (22Z, 22Z)
+22Z = (22C, 22C)
+";
+
+    example_tests! {
+        Day8,
+        part_one: [(EXAMPLE_ONE, 2), (EXAMPLE_TWO, 6)],
+        part_two: [(EXAMPLE_THREE, 6), (EXAMPLE_UNALIGNED, 7)],
+    }
 
-        if debug {
-            println!("{:#?}", keys);
+    fn ghost(start: u64, period: u64, cycle_hits: &[u64]) -> Ghost {
+        Ghost {
+            start,
+            period,
+            prefix_hits: Vec::new(),
+            cycle_hits: cycle_hits.to_vec(),
         }
+    }
 
-        let path_lengths = keys
-            .into_iter()
-            .map(|key| {
-                let mut key = key;
-                (|| {
-                    let mut instructions_iter = instructions.chars();
-                    let mut direction = instructions_iter.next().context("")?;
-
-                    let mut step_count = 0;
-
-                    loop {
-                        let value = map.get(key).context("Map does not contain key")?;
-                        if direction == 'L' {
-                            key = &value.0;
-                        } else {
-                            key = &value.1;
-                        }
-
-                        step_count += 1;
-
-                        if key.ends_with("Z") {
-                            break;
-                        }
-
-                        direction = if let Some(direction) = instructions_iter.next() {
-                            direction
-                        } else {
-                            instructions_iter = instructions.chars();
-                            instructions_iter.next().context("")?
-                        }
-                    }
-
-                    Ok(step_count)
-                })()
-            })
-            .collect::<Result<Vec<u64>>>()?;
-
-        let step_count = path_lengths
-            .into_iter()
-            .fold(None::<u64>, |lcm_res, length| {
-                if let Some(lcm_res) = lcm_res {
-                    Some(lcm(lcm_res, length))
-                } else {
-                    Some(length)
-                }
-            })
-            .context("No least common multiple")?;
-
-        let step_count = i64::try_from(step_count)?;
-        Ok(step_count)
+    #[test]
+    fn finds_the_first_common_hit() {
+        let ghosts = [ghost(1, 4, &[2, 3]), ghost(2, 6, &[5])];
+        assert_eq!(first_common_hit(&ghosts).unwrap(), 11);
+
+        let err = first_common_hit(&[ghost(0, 2, &[0]), ghost(0, 4, &[1])]).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NoSolution);
+    }
+
+    #[test]
+    fn reports_a_common_hit_past_u64() {
+        // Both ghosts hit on steps 3 mod their period, but only from step 5
+        // on, so the first common hit is one lcm past 3, just over u64::MAX.
+        let ghosts = [
+            ghost(5, u32::MAX as u64, &[u32::MAX as u64 + 3]),
+            ghost(5, u32::MAX as u64 + 2, &[u32::MAX as u64 + 5]),
+        ];
+
+        let err = first_common_hit(&ghosts).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NoSolution);
+        assert!(err.message.contains(&(u64::MAX as u128 + 3).to_string()));
     }
 }
@@ -1,29 +1,23 @@
-use crate::Solvable;
+use crate::parse::parse_lines;
+use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
 
 use anyhow::Result;
-use std::fs::read_to_string;
-use std::path::Path;
 
 pub struct Day9 {}
 
 impl Solvable for Day9 {
+    type Parsed = Vec<Vec<i32>>;
+
     fn get_day() -> u32 {
         9
     }
 
-    fn solve_part_one(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let reports = read_to_string(path)?
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|value| value.parse().map_err(anyhow::Error::msg))
-                    .collect::<Result<Vec<i32>>>()
-            })
-            .collect::<Result<Vec<Vec<i32>>>>()?;
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| Ok(line.numbers()?))
+    }
 
+    fn solve_part_one(reports: Self::Parsed, trace: &Trace) -> Result<Answer> {
         let extrapolated_sum: i32 = reports
             .into_iter()
             .map(|report| {
@@ -44,9 +38,7 @@ impl Solvable for Day9 {
                             Some(curr_value)
                         });
 
-                    if debug {
-                        println!("{:?}", report_diff);
-                    }
+                    trace!(trace, Step, "{:?}", report_diff);
 
                     let value_sum: i32 = report_diff.iter().sum();
 
@@ -63,23 +55,10 @@ impl Solvable for Day9 {
             })
             .sum();
 
-        let extrapolated_sum = i64::try_from(extrapolated_sum)?;
-        Ok(extrapolated_sum)
+        Ok(extrapolated_sum.into())
     }
 
-    fn solve_part_two(debug: bool) -> Result<i64> {
-        let path = format!("src/inputs/day{}.txt", Self::get_day());
-        let path = Path::new(&path);
-
-        let reports = read_to_string(path)?
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|value| value.parse().map_err(anyhow::Error::msg))
-                    .collect::<Result<Vec<i32>>>()
-            })
-            .collect::<Result<Vec<Vec<i32>>>>()?;
-
+    fn solve_part_two(reports: Self::Parsed, trace: &Trace) -> Result<Answer> {
         let extrapolated_values = reports
             .into_iter()
             .map(|report| {
@@ -100,9 +79,7 @@ impl Solvable for Day9 {
                             Some(curr_value)
                         });
 
-                    if debug {
-                        println!("{:?}", report_diff);
-                    }
+                    trace!(trace, Step, "{:?}", report_diff);
 
                     let value_sum: i32 = report_diff.iter().sum();
 
@@ -119,12 +96,27 @@ impl Solvable for Day9 {
             })
             .collect::<Vec<i32>>();
 
-        if debug {
-            println!("{:?}", extrapolated_values)
-        }
+        trace!(trace, Step, "{:?}", extrapolated_values);
 
         let extrapolated_sum: i32 = extrapolated_values.iter().sum();
-        let extrapolated_sum = i64::try_from(extrapolated_sum)?;
-        Ok(extrapolated_sum)
+        Ok(extrapolated_sum.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day9;
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    example_tests! {
+        Day9,
+        part_one: [(EXAMPLE, 114)],
+        part_two: [(EXAMPLE, 2)],
     }
 }
@@ -18,6 +18,7 @@ pub mod day9;
 pub use day1::Day1;
 pub use day10::Day10;
 pub use day11::Day11;
+pub use day12::Day12;
 pub use day13::Day13;
 pub use day14::Day14;
 pub use day15::Day15;
@@ -30,3 +31,33 @@ pub use day6::Day6;
 pub use day7::Day7;
 pub use day8::Day8;
 pub use day9::Day9;
+
+use crate::Solver;
+
+/// Every implemented day, in day order.
+pub static REGISTRY: &[&dyn Solver] = &[
+    &Day1 {},
+    &Day2 {},
+    &Day3 {},
+    &Day4 {},
+    &Day5 {},
+    &Day6 {},
+    &Day7 {},
+    &Day8 {},
+    &Day9 {},
+    &Day10 {},
+    &Day11 {},
+    &Day12 {},
+    &Day13 {},
+    &Day14 {},
+    &Day15 {},
+    &Day16 {},
+];
+
+pub fn get_solver(day: u32) -> Option<&'static dyn Solver> {
+    REGISTRY.iter().find(|solver| solver.day() == day).copied()
+}
+
+pub fn implemented_days() -> impl Iterator<Item = u32> {
+    REGISTRY.iter().map(|solver| solver.day())
+}
@@ -0,0 +1,115 @@
+use crate::Part;
+
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The puzzle input is not in the expected format.
+    Malformed,
+    /// The input parsed but the puzzle has no answer for it.
+    NoSolution,
+    /// Something the solver assumes to always hold did not.
+    Invariant,
+}
+
+impl ErrorKind {
+    /// A stable identifier for machine-readable output.
+    pub fn id(&self) -> &'static str {
+        match self {
+            ErrorKind::Malformed => "malformed",
+            ErrorKind::NoSolution => "no_solution",
+            ErrorKind::Invariant => "invariant",
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::Malformed => write!(f, "input malformed"),
+            ErrorKind::NoSolution => write!(f, "no solution found"),
+            ErrorKind::Invariant => write!(f, "internal invariant violated"),
+        }
+    }
+}
+
+/// An error from parsing or solving a part, along with where it happened.
+///
+/// Solvers return these through `anyhow`; the runner fills in the day and
+/// part and wraps any other error with the kind implied by the failing step.
+#[derive(Debug)]
+pub struct PuzzleError {
+    pub kind: ErrorKind,
+    pub day: Option<u32>,
+    pub part: Option<Part>,
+    /// 1-based line of the input.
+    pub line: Option<usize>,
+    /// 1-based column of `line`.
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl PuzzleError {
+    pub fn new(kind: ErrorKind, message: impl Display) -> Self {
+        PuzzleError {
+            kind,
+            day: None,
+            part: None,
+            line: None,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn malformed(message: impl Display) -> Self {
+        Self::new(ErrorKind::Malformed, message)
+    }
+
+    pub fn no_solution(message: impl Display) -> Self {
+        Self::new(ErrorKind::NoSolution, message)
+    }
+
+    pub fn invariant(message: impl Display) -> Self {
+        Self::new(ErrorKind::Invariant, message)
+    }
+
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// Recovers a `PuzzleError` from `err`, or wraps it as a new one of `kind`.
+    pub fn from_anyhow(err: anyhow::Error, kind: ErrorKind) -> Self {
+        match err.downcast::<PuzzleError>() {
+            Ok(err) => err,
+            Err(err) => Self::new(kind, format!("{:#}", err)),
+        }
+    }
+}
+
+impl Display for PuzzleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.day, self.part) {
+            (Some(day), Some(part)) => write!(f, "Day {} part {}: ", day, part)?,
+            (Some(day), None) => write!(f, "Day {}: ", day)?,
+            _ => (),
+        }
+
+        write!(f, "{}", self.kind)?;
+
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, " at line {}, column {}", line, column)?,
+            (Some(line), None) => write!(f, " at line {}", line)?,
+            _ => (),
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for PuzzleError {}
@@ -0,0 +1,217 @@
+use std::ops::Not;
+use strum_macros::EnumIs;
+
+/// A compass direction on a grid where `y` grows downwards.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, EnumIs, Debug)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Every direction, clockwise from `Up`.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// The four non-diagonal directions, clockwise from `Up`.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The change in `(x, y)` of one step this way.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.offset();
+        dx != 0 && dy != 0
+    }
+
+    fn rotate(self, eighths: usize) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|direction| *direction == self)
+            .unwrap_or_default();
+        Self::ALL[(index + eighths) % 8]
+    }
+
+    /// Turns 90 degrees counter-clockwise.
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Turns 90 degrees clockwise.
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+}
+
+impl Not for Direction {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        self.reverse()
+    }
+}
+
+/// A position on a grid, with `(0, 0)` in the top left.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Debug)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub fn new(x: usize, y: usize) -> Self {
+        Point { x, y }
+    }
+
+    /// One step towards `direction`, or `None` past the top or left edge.
+    pub fn checked_step(self, direction: Direction) -> Option<Self> {
+        let (dx, dy) = direction.offset();
+        Some(Point {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+
+    /// One step towards `direction`, wrapping around within a `width` by
+    /// `height` area.
+    pub fn wrapping_step(self, direction: Direction, width: usize, height: usize) -> Self {
+        let (dx, dy) = direction.offset();
+        let wrap = |value: usize, delta: isize, length: usize| {
+            (value as isize + delta).rem_euclid(length as isize) as usize
+        };
+
+        Point {
+            x: wrap(self.x, dx, width),
+            y: wrap(self.y, dy, height),
+        }
+    }
+
+    /// The distance moving only horizontally and vertically.
+    pub fn manhattan(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// The distance moving diagonally as well, so every neighbour is 1 away.
+    pub fn chebyshev(&self, other: &Self) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point};
+
+    #[test]
+    fn turns_and_reverse() {
+        for (index, direction) in Direction::ALL.into_iter().enumerate() {
+            assert_eq!(direction.turn_right(), Direction::ALL[(index + 2) % 8]);
+            assert_eq!(direction.turn_left(), Direction::ALL[(index + 6) % 8]);
+            assert_eq!(direction.reverse(), Direction::ALL[(index + 4) % 8]);
+            assert_eq!(!direction, direction.reverse());
+            assert_eq!(direction.turn_left().turn_right(), direction);
+
+            let (dx, dy) = direction.offset();
+            let (rx, ry) = direction.reverse().offset();
+            assert_eq!((dx + rx, dy + ry), (0, 0));
+            assert_eq!(direction.is_diagonal(), index % 2 == 1);
+        }
+
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::UpRight.turn_right(), Direction::DownRight);
+    }
+
+    #[test]
+    fn checked_step_stops_at_zero() {
+        let origin = Point::new(0, 0);
+        assert_eq!(origin.checked_step(Direction::Up), None);
+        assert_eq!(origin.checked_step(Direction::Left), None);
+        assert_eq!(origin.checked_step(Direction::UpRight), None);
+        assert_eq!(origin.checked_step(Direction::DownLeft), None);
+        assert_eq!(
+            origin.checked_step(Direction::Right),
+            Some(Point::new(1, 0))
+        );
+        assert_eq!(
+            origin.checked_step(Direction::DownRight),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(
+            Point::new(3, 2).checked_step(Direction::UpLeft),
+            Some(Point::new(2, 1))
+        );
+    }
+
+    #[test]
+    fn wrapping_step() {
+        assert_eq!(
+            Point::new(0, 0).wrapping_step(Direction::UpLeft, 4, 3),
+            Point::new(3, 2)
+        );
+        assert_eq!(
+            Point::new(3, 2).wrapping_step(Direction::DownRight, 4, 3),
+            Point::new(0, 0)
+        );
+        assert_eq!(
+            Point::new(1, 1).wrapping_step(Direction::Right, 4, 3),
+            Point::new(2, 1)
+        );
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(1, 5);
+        let b = Point::new(4, 1);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(b.manhattan(&a), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.manhattan(&a), 0);
+        assert_eq!(Point::new(2, 2).chebyshev(&Point::new(3, 3)), 1);
+    }
+}
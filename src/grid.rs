@@ -0,0 +1,312 @@
+use crate::geometry::{Direction, Point};
+use crate::parse::Span;
+use crate::PuzzleError;
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A rectangular grid of cells stored row by row, indexed by `(x, y)` with
+/// `(0, 0)` in the top left.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, PuzzleError> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+
+        if let Some(index) = rows.iter().position(|row| row.len() != width) {
+            return Err(PuzzleError::malformed(format!(
+                "Expected a row of {} cells, found {}.",
+                width,
+                rows[index].len()
+            ))
+            .at_line(index + 1));
+        }
+
+        Ok(Grid {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+        })
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.index(x, y).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.index(x, y).map(|index| &mut self.cells[index])
+    }
+
+    /// Replaces the cell at `(x, y)`, returning the previous value, or `None`
+    /// if it is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).filter_map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        (0..self.height).filter_map(move |y| self.get(x, y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every cell along with its `(x, y)`, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| ((index % self.width, index / self.width), cell))
+    }
+
+    /// The `(x, y)` of every cell matching `predicate`, row by row.
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.cells()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// The in-bounds cells above, right of, below and left of `(x, y)`.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.steps(x, y, &Direction::CARDINAL)
+    }
+
+    /// The in-bounds cells around `(x, y)`, diagonals included, clockwise from
+    /// the one above.
+    pub fn neighbors_diagonal(
+        &self,
+        x: usize,
+        y: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.steps(x, y, &Direction::ALL)
+    }
+
+    fn steps(
+        &self,
+        x: usize,
+        y: usize,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        directions.iter().filter_map(move |direction| {
+            let point = Point::new(x, y).checked_step(*direction)?;
+            self.index(point.x, point.y).map(|_| point.into())
+        })
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Builds a `width` by `height` grid where `(x, y)` takes the cell of this
+    /// grid at `source(x, y)`.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self.cells[source(x, y)].clone())
+            .collect();
+
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| x * self.width + y)
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| {
+            (self.height - 1 - x) * self.width + y
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| {
+            x * self.width + (self.width - 1 - y)
+        })
+    }
+}
+
+/// Parses one cell per character, like the strum-derived tile enums.
+impl<T> FromStr for Grid<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Err = PuzzleError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let rows = input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| Span::numbered(line, index + 1).cells())
+            .collect::<Result<Vec<Vec<T>>, PuzzleError>>()?;
+
+        if rows.is_empty() {
+            return Err(PuzzleError::malformed("Expected at least one row."));
+        }
+
+        Self::from_rows(rows)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rows = self
+            .rows()
+            .map(|row| row.iter().map(T::to_string).collect::<String>())
+            .collect::<Vec<String>>();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    /// ```text
+    /// abc
+    /// def
+    /// ```
+    fn letters() -> Grid<char> {
+        "abc\ndef".parse().unwrap()
+    }
+
+    #[test]
+    fn parses_and_indexes() {
+        let grid = letters();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 1), Some(&'f'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn set_returns_the_old_cell() {
+        let mut grid = letters();
+        assert_eq!(grid.set(1, 0, 'x'), Some('b'));
+        assert_eq!(grid.set(5, 5, 'x'), None);
+        assert_eq!(grid.to_string(), "axc\ndef");
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = Grid::from_rows(vec![vec![1, 2], vec![3, 4], vec![5]]).unwrap_err();
+        assert_eq!(err.line, Some(3));
+
+        let err = "ab\nabc".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(err.line, Some(2));
+
+        assert!("".parse::<Grid<char>>().is_err());
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = letters();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad");
+
+        let turned = (0..4).fold(grid.clone(), |grid, _| grid.rotate_clockwise());
+        assert_eq!(turned, grid);
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    fn neighbors_at_edges_and_corners() {
+        let grid = Grid::filled(3, 3, 0);
+
+        assert_eq!(grid.neighbors(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors(2, 2).collect::<Vec<_>>(), [(2, 1), (1, 2)]);
+        assert_eq!(
+            grid.neighbors(1, 0).collect::<Vec<_>>(),
+            [(2, 0), (1, 1), (0, 0)]
+        );
+        assert_eq!(grid.neighbors(1, 1).count(), 4);
+
+        assert_eq!(
+            grid.neighbors_diagonal(0, 0).collect::<Vec<_>>(),
+            [(1, 0), (1, 1), (0, 1)]
+        );
+        assert_eq!(
+            grid.neighbors_diagonal(2, 0).collect::<Vec<_>>(),
+            [(2, 1), (1, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors_diagonal(1, 1).count(), 8);
+        assert_eq!(grid.neighbors_diagonal(0, 1).count(), 5);
+    }
+
+    #[test]
+    fn positions_are_row_by_row() {
+        let grid: Grid<char> = "#.#\n.#.".parse().unwrap();
+        assert_eq!(
+            grid.positions(|cell| *cell == '#').collect::<Vec<_>>(),
+            [(0, 0), (2, 0), (1, 1)]
+        );
+    }
+}
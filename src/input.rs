@@ -0,0 +1,47 @@
+use anyhow::{Context, Result};
+use std::fs::read_to_string;
+use std::io::{stdin, Read};
+use std::path::PathBuf;
+
+pub const DEFAULT_INPUTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/inputs");
+
+/// Where the runner reads puzzle input from.
+#[derive(Debug, PartialEq)]
+pub enum InputSource {
+    /// A directory containing one `day{N}.txt` file per day.
+    Directory(PathBuf),
+    /// A single file, used for whichever day is selected.
+    File(PathBuf),
+    Stdin,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Directory(PathBuf::from(DEFAULT_INPUTS_DIR))
+    }
+}
+
+impl InputSource {
+    pub fn is_single_day(&self) -> bool {
+        !matches!(self, InputSource::Directory(_))
+    }
+
+    pub fn read(&self, day: u32) -> Result<String> {
+        match self {
+            InputSource::Directory(dir) => {
+                let path = dir.join(format!("day{}.txt", day));
+                read_to_string(&path)
+                    .with_context(|| format!("Could not read input {}.", path.display()))
+            }
+            InputSource::File(path) => read_to_string(path)
+                .with_context(|| format!("Could not read input {}.", path.display())),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                stdin()
+                    .read_to_string(&mut input)
+                    .context("Could not read input from stdin.")?;
+                Ok(input)
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+
+/// The half-open range of integers `start..end`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Interval {
+    pub fn new(start: i64, end: i64) -> Self {
+        Interval { start, end }
+    }
+
+    /// The `length` integers starting at `start`, like the almanac's ranges.
+    pub fn from_length(start: i64, length: i64) -> Self {
+        Interval::new(start, start + length)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> i64 {
+        (self.end - self.start).max(0)
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let interval = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        (!interval.is_empty()).then_some(interval)
+    }
+
+    /// Moves the whole interval by `offset`.
+    pub fn shift(&self, offset: i64) -> Self {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// A set of integers, kept as sorted intervals that neither overlap nor
+/// touch, so every set has exactly one representation.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    /// Sorts and coalesces `intervals`, dropping empty ones.
+    fn normalize(mut intervals: Vec<Interval>) -> Self {
+        intervals.retain(|interval| !interval.is_empty());
+        intervals.sort();
+
+        let mut coalesced: Vec<Interval> = Vec::with_capacity(intervals.len());
+        for interval in intervals {
+            match coalesced.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => coalesced.push(interval),
+            }
+        }
+
+        IntervalSet {
+            intervals: coalesced,
+        }
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        let mut intervals = std::mem::take(&mut self.intervals);
+        intervals.push(interval);
+        *self = IntervalSet::normalize(intervals);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many integers are in the set.
+    pub fn len(&self) -> i64 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.intervals
+            .binary_search_by(|interval| {
+                if interval.end <= value {
+                    Ordering::Less
+                } else if interval.start > value {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    pub fn min(&self) -> Option<i64> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<i64> {
+        self.intervals.last().map(|interval| interval.end - 1)
+    }
+
+    /// The coalesced intervals, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &Interval> + '_ {
+        self.intervals.iter()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.iter()
+            .flat_map(|interval| {
+                other
+                    .iter()
+                    .filter_map(|other| interval.intersection(other))
+            })
+            .collect()
+    }
+
+    /// Everything in this set that isn't in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+
+        for interval in self.iter() {
+            let mut start = interval.start;
+            for other in other.iter() {
+                if other.end <= start || other.start >= interval.end {
+                    continue;
+                }
+                intervals.push(Interval::new(start, other.start));
+                start = other.end;
+            }
+            intervals.push(Interval::new(start, interval.end));
+        }
+
+        IntervalSet::normalize(intervals)
+    }
+
+    /// Shifts the parts of the set inside each piece's interval by its
+    /// offset, leaving the rest where it is.
+    ///
+    /// When pieces overlap, the first one a value falls in moves it.
+    pub fn map_offsets(&self, pieces: impl IntoIterator<Item = (Interval, i64)>) -> Self {
+        let mut unmapped = self.clone();
+        let mut mapped = Vec::new();
+
+        for (source, offset) in pieces {
+            let hit = unmapped.intersection(&source.into());
+            mapped.extend(hit.iter().map(|interval| interval.shift(offset)));
+            unmapped = unmapped.difference(&hit);
+        }
+
+        mapped.extend(unmapped.intervals);
+        IntervalSet::normalize(mapped)
+    }
+}
+
+impl From<Interval> for IntervalSet {
+    fn from(interval: Interval) -> Self {
+        IntervalSet::normalize(vec![interval])
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(intervals: I) -> Self {
+        IntervalSet::normalize(intervals.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, IntervalSet};
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    fn intervals(set: &IntervalSet) -> Vec<(i64, i64)> {
+        set.iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    #[test]
+    fn coalesces_touching_and_overlapping() {
+        assert_eq!(intervals(&set(&[(5, 8), (0, 3), (3, 5)])), [(0, 8)]);
+        assert_eq!(
+            intervals(&set(&[(0, 4), (2, 6), (10, 12)])),
+            [(0, 6), (10, 12)]
+        );
+        assert_eq!(intervals(&set(&[(0, 10), (2, 3)])), [(0, 10)]);
+        assert_eq!(intervals(&set(&[(0, 2), (3, 5)])), [(0, 2), (3, 5)]);
+        assert!(set(&[(4, 4), (6, 2)]).is_empty());
+
+        let mut inserted = set(&[(0, 2), (4, 6)]);
+        inserted.insert(Interval::new(2, 4));
+        assert_eq!(intervals(&inserted), [(0, 6)]);
+    }
+
+    #[test]
+    fn queries() {
+        let set = set(&[(0, 3), (10, 12)]);
+        assert_eq!(set.len(), 5);
+        assert_eq!((set.min(), set.max()), (Some(0), Some(11)));
+        assert!(set.contains(2) && set.contains(10) && set.contains(11));
+        assert!(!set.contains(3) && !set.contains(9) && !set.contains(12));
+    }
+
+    #[test]
+    fn difference_with_one_interval_covering_several() {
+        let holes = set(&[(2, 4), (6, 7), (9, 12)]);
+        assert_eq!(
+            intervals(&set(&[(0, 10)]).difference(&holes)),
+            [(0, 2), (4, 6), (7, 9)]
+        );
+        assert!(set(&[(3, 4), (6, 7)])
+            .difference(&set(&[(0, 10)]))
+            .is_empty());
+        assert_eq!(
+            intervals(&set(&[(0, 3), (5, 8)]).difference(&set(&[(2, 6)]))),
+            [(0, 2), (6, 8)]
+        );
+    }
+
+    #[test]
+    fn intersection_and_union() {
+        let left = set(&[(0, 5), (8, 12)]);
+        let right = set(&[(3, 9), (11, 20)]);
+        assert_eq!(
+            intervals(&left.intersection(&right)),
+            [(3, 5), (8, 9), (11, 12)]
+        );
+        assert!(left.intersection(&set(&[(5, 8)])).is_empty());
+        assert_eq!(intervals(&left.union(&right)), [(0, 20)]);
+    }
+
+    #[test]
+    fn map_offsets_lets_the_first_piece_win() {
+        let pieces = [
+            (Interval::new(0, 5), 100),
+            (Interval::new(3, 8), 200),
+            (Interval::new(20, 30), -20),
+        ];
+        assert_eq!(
+            intervals(&set(&[(0, 10)]).map_offsets(pieces)),
+            [(8, 10), (100, 105), (205, 208)]
+        );
+        assert_eq!(intervals(&set(&[(25, 27)]).map_offsets(pieces)), [(5, 7)]);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let left = set(&[(-3, 2), (4, 9), (12, 13)]);
+        let right = set(&[(0, 5), (8, 14)]);
+        let members = |set: &IntervalSet| {
+            (-5..16)
+                .filter(|&value| set.contains(value))
+                .collect::<Vec<_>>()
+        };
+        let filtered =
+            |keep: &dyn Fn(i64) -> bool| (-5..16).filter(|&value| keep(value)).collect::<Vec<_>>();
+
+        assert_eq!(
+            members(&left.union(&right)),
+            filtered(&|value| left.contains(value) || right.contains(value))
+        );
+        assert_eq!(
+            members(&left.intersection(&right)),
+            filtered(&|value| left.contains(value) && right.contains(value))
+        );
+        assert_eq!(
+            members(&left.difference(&right)),
+            filtered(&|value| left.contains(value) && !right.contains(value))
+        );
+    }
+}
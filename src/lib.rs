@@ -0,0 +1,151 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod days;
+pub mod error;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod math;
+pub mod parse;
+pub mod piecewise;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+#[cfg(test)]
+mod testing;
+pub mod trace;
+
+pub use answer::Answer;
+pub use error::PuzzleError;
+pub use report::PartReport;
+pub use trace::{Trace, Tracer};
+
+use error::ErrorKind;
+use trace::trace;
+
+use anyhow::Result;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(part: &str) -> Result<Self, Self::Err> {
+        match part {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(String::from("expected 1 or 2")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RunMode {
+    Result,
+    ResultPartOne,
+    ResultPartTwo,
+}
+
+impl RunMode {
+    pub fn runs(&self, part: Part) -> bool {
+        match part {
+            Part::One => [RunMode::ResultPartOne, RunMode::Result].contains(self),
+            Part::Two => [RunMode::ResultPartTwo, RunMode::Result].contains(self),
+        }
+    }
+}
+
+pub trait Solvable {
+    /// The puzzle input after parsing, shared by both parts.
+    type Parsed;
+
+    fn get_day() -> u32;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+
+    fn solve_part_one(parsed: Self::Parsed, trace: &Trace) -> Result<Answer>;
+    fn solve_part_two(parsed: Self::Parsed, trace: &Trace) -> Result<Answer>;
+}
+
+/// Object safe counterpart of [`Solvable`], implemented for every day so days
+/// can be stored in the registry and dispatched at runtime.
+pub trait Solver: Sync {
+    fn day(&self) -> u32;
+
+    /// Parses `input` and solves `part`, timing both steps separately.
+    fn run_part(&self, part: Part, input: &str, tracer: &Tracer) -> PartReport;
+}
+
+impl<T: Solvable + Sync> Solver for T {
+    fn day(&self) -> u32 {
+        T::get_day()
+    }
+
+    fn run_part(&self, part: Part, input: &str, tracer: &Tracer) -> PartReport {
+        let trace = tracer.for_part(T::get_day(), part);
+
+        let parse_start = Instant::now();
+        let parsed = T::parse(input);
+        let parse_time = parse_start.elapsed();
+
+        let (answer, solve_time) = match parsed {
+            Ok(parsed) => {
+                let solve_start = Instant::now();
+                let answer = match part {
+                    Part::One => T::solve_part_one(parsed, &trace),
+                    Part::Two => T::solve_part_two(parsed, &trace),
+                };
+                let solve_time = solve_start.elapsed();
+                (
+                    answer.map_err(|err| PuzzleError::from_anyhow(err, ErrorKind::Invariant)),
+                    solve_time,
+                )
+            }
+            Err(err) => (
+                Err(PuzzleError::from_anyhow(err, ErrorKind::Malformed)),
+                Duration::ZERO,
+            ),
+        };
+
+        trace!(
+            trace,
+            Summary,
+            "Parsed in {:.2?}, solved in {:.2?}",
+            parse_time,
+            solve_time
+        );
+
+        let answer = answer.map_err(|err| PuzzleError {
+            day: Some(T::get_day()),
+            part: Some(part),
+            ..err
+        });
+
+        PartReport {
+            day: T::get_day(),
+            part,
+            answer,
+            parse_time,
+            solve_time,
+        }
+    }
+}
@@ -1,65 +1,254 @@
-mod days;
-use days::*;
+mod cli;
 
-use anyhow::Result;
+use cli::{AnswersMode, BenchOptions, Command, DaySelection, RunOptions};
+use my_project::answers::{Answers, Verdict};
+use my_project::bench::{self, Baseline, BenchResult};
+use my_project::report::{self, Format};
+use my_project::runner::{self, Task};
+use my_project::scaffold;
+use my_project::{days, Part, PuzzleError, RunMode, Solver, Tracer};
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum RunMode {
-    Result,
-    DebugPartOne,
-    DebugPartTwo,
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+use std::time::Instant;
+
+fn build_tracer(options: &RunOptions) -> Result<Tracer> {
+    let Some(level) = options.trace_level else {
+        return Ok(Tracer::default());
+    };
+
+    let tracer = Tracer::new(level);
+    let tracer = match &options.trace_days {
+        Some(days) => tracer.with_days(days.clone()),
+        None => tracer,
+    };
+    match (&options.trace_file, options.format) {
+        (Some(path), _) => tracer.with_file(path),
+        (None, Format::Text) => Ok(tracer),
+        // Traces on stdout would break the JSON.
+        (None, Format::Json | Format::JsonLines) => Ok(tracer.with_stderr()),
+    }
 }
 
-pub trait Solvable {
-    fn get_day() -> u32;
+fn resolve_solvers(selection: &DaySelection) -> Result<Vec<&'static dyn Solver>> {
+    match selection {
+        DaySelection::All => Ok(days::REGISTRY.to_vec()),
+        DaySelection::Days(days) => days
+            .iter()
+            .map(|day| {
+                days::get_solver(*day).ok_or_else(|| {
+                    anyhow!(
+                        "Day {} is not implemented. Implemented days: {:?}",
+                        day,
+                        days::implemented_days().collect::<Vec<u32>>()
+                    )
+                })
+            })
+            .collect(),
+    }
+}
 
-    fn solve_part_one(debug: bool) -> Result<i64>;
-    fn solve_part_two(debug: bool) -> Result<i64>;
+fn build_tasks<'a>(
+    solvers: &[&'a dyn Solver],
+    inputs: &'a [String],
+    run_mode: RunMode,
+) -> Vec<Task<'a>> {
+    solvers
+        .iter()
+        .zip(inputs.iter())
+        .flat_map(|(solver, input)| {
+            [Part::One, Part::Two]
+                .into_iter()
+                .filter(|part| run_mode.runs(*part))
+                .map(|part| Task {
+                    solver: *solver,
+                    part,
+                    input,
+                })
+        })
+        .collect()
+}
 
-    fn solve(run_mode: RunMode) -> Result<()> {
-        println!("Day {}:", Self::get_day());
+fn main() -> Result<()> {
+    let command = cli::parse_args(std::env::args().skip(1))
+        .map_err(|err| anyhow!("{}\n\n{}", err, cli::USAGE))?;
 
-        if [RunMode::DebugPartOne, RunMode::Result].contains(&run_mode) {
-            match Self::solve_part_one(run_mode == RunMode::DebugPartOne) {
-                Ok(result) => {
-                    println!("\tPart 1 - {}", result);
-                }
-                Err(err) => {
-                    println!("Part 1 Error - {}", err);
-                }
-            }
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
         }
-        if [RunMode::DebugPartTwo, RunMode::Result].contains(&run_mode) {
-            match Self::solve_part_two(run_mode == RunMode::DebugPartTwo) {
-                Ok(result) => {
-                    println!("\tPart 2 - {}", result);
-                }
-                Err(err) => {
-                    println!("Part 2 Error - {}", err);
+        Command::Run(options) => run(options),
+        Command::Bench(options) => bench(options),
+        Command::NewDay(day) => new_day(day),
+    }
+}
+
+fn new_day(day: u32) -> Result<()> {
+    let changed = scaffold::new_day(Path::new(scaffold::DEFAULT_SOURCE_DIR), day)?;
+
+    println!("Created day {}:", day);
+    changed
+        .iter()
+        .for_each(|path| println!("    {}", path.display()));
+
+    Ok(())
+}
+
+fn bench(options: BenchOptions) -> Result<()> {
+    let solvers = resolve_solvers(&options.days)?;
+    let inputs = solvers
+        .iter()
+        .map(|solver| options.input.read(solver.day()))
+        .collect::<Result<Vec<String>>>()?;
+    let baseline = options.compare.as_deref().map(Baseline::load).transpose()?;
+
+    let results = build_tasks(&solvers, &inputs, options.run_mode)
+        .into_iter()
+        .map(|task| {
+            println!(
+                "Benchmarking day {} part {}...",
+                task.solver.day(),
+                task.part
+            );
+            bench::bench(
+                task.solver,
+                task.part,
+                task.input,
+                options.warmup,
+                options.runs,
+            )
+        })
+        .collect::<Result<Vec<BenchResult>, PuzzleError>>()?;
+
+    println!(
+        "\n{}",
+        bench::bench_table(&results, baseline.as_ref(), &options.tolerance)
+    );
+
+    if let Some(path) = &options.save {
+        Baseline::save(path, &results)?;
+        println!("\nSaved {} result(s) to {}.", results.len(), path.display());
+    }
+
+    let regressions = results
+        .iter()
+        .filter(|result| {
+            baseline
+                .as_ref()
+                .and_then(|baseline| baseline.get(result.day, result.part))
+                .is_some_and(|previous| bench::is_regression(result, previous, &options.tolerance))
+        })
+        .count();
+    if regressions > 0 {
+        bail!(
+            "{} part(s) regressed by more than {}%.",
+            regressions,
+            options.tolerance.threshold
+        );
+    }
+
+    Ok(())
+}
+
+fn run(options: RunOptions) -> Result<()> {
+    let tracer = build_tracer(&options)?;
+
+    let solvers = resolve_solvers(&options.days)?;
+    let inputs = solvers
+        .iter()
+        .map(|solver| options.input.read(solver.day()))
+        .collect::<Result<Vec<String>>>()?;
+    let tasks = build_tasks(&solvers, &inputs, options.run_mode);
+
+    let expected = match options.answers_mode {
+        AnswersMode::Verify => Some(Answers::load(&options.answers_file)?),
+        _ => None,
+    };
+
+    let run_start = Instant::now();
+    let mut printed_day = None;
+    let mut verdicts = Vec::new();
+    let reports = runner::run_tasks(&tasks, options.jobs, &tracer, |report| {
+        let verdict = expected.as_ref().map(|answers| answers.verify(report));
+
+        match options.format {
+            Format::Text => {
+                if printed_day != Some(report.day) {
+                    println!("Day {}:", report.day);
+                    printed_day = Some(report.day);
                 }
+                report.print();
             }
+            Format::JsonLines => println!("{}", report.to_json(verdict.as_ref())),
+            Format::Json => (),
         }
 
-        Ok(())
+        verdicts.push(verdict);
+    });
+    let wall_time = run_start.elapsed();
+
+    match options.format {
+        Format::Text => {
+            if solvers.len() > 1 {
+                println!("\n{}", report::summary_table(&reports));
+                println!(
+                    "Finished in {:.2?} with {} job(s).",
+                    wall_time, options.jobs
+                );
+            }
+
+            if expected.is_some() {
+                println!("\nVerification:");
+                reports
+                    .iter()
+                    .zip(verdicts.iter().flatten())
+                    .for_each(|(report, verdict)| {
+                        println!("\tDay {} Part {} - {}", report.day, report.part, verdict);
+                    });
+            }
+        }
+        Format::Json => println!("{}", report::json_document(&reports, &verdicts, wall_time)),
+        Format::JsonLines => (),
     }
-}
 
-fn main() -> Result<()> {
-    Day1::solve(RunMode::Result)?;
-    Day2::solve(RunMode::Result)?;
-    Day3::solve(RunMode::Result)?;
-    Day4::solve(RunMode::Result)?;
-    Day5::solve(RunMode::Result)?;
-    Day6::solve(RunMode::Result)?;
-    Day7::solve(RunMode::Result)?;
-    Day8::solve(RunMode::Result)?;
-    Day9::solve(RunMode::Result)?;
-    Day10::solve(RunMode::Result)?;
-    Day11::solve(RunMode::Result)?;
-    // Day12::solve(RunMode::Result)?;
-    Day13::solve(RunMode::Result)?;
-    Day14::solve(RunMode::Result)?;
-    Day15::solve(RunMode::Result)?;
+    if options.answers_mode == AnswersMode::Record {
+        let mut answers = Answers::load(&options.answers_file)?;
 
-    Ok(())
+        let recorded = reports
+            .iter()
+            .filter(|report| answers.record(report))
+            .count();
+        answers.save(&options.answers_file)?;
+
+        // Keep stdout parseable when printing JSON.
+        let message = format!(
+            "Recorded {} answer(s) to {}.",
+            recorded,
+            options.answers_file.display()
+        );
+        match options.format {
+            Format::Text => println!("\n{}", message),
+            Format::Json | Format::JsonLines => eprintln!("{}", message),
+        }
+    }
+
+    let errors = reports
+        .iter()
+        .filter(|report| report.answer.is_err())
+        .count();
+    let mismatches = verdicts
+        .iter()
+        .filter(|verdict| matches!(verdict, Some(Verdict::Fail { .. })))
+        .count();
+    match (errors, mismatches) {
+        (0, 0) => Ok(()),
+        (errors, 0) => bail!("{} part(s) failed.", errors),
+        (0, mismatches) => bail!("{} answer(s) failed verification.", mismatches),
+        (errors, mismatches) => bail!(
+            "{} part(s) failed and {} answer(s) failed verification.",
+            errors,
+            mismatches
+        ),
+    }
 }
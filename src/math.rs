@@ -0,0 +1,181 @@
+/// The largest integer whose square is at most `n`, found with Newton's method
+/// so there is no floating point rounding at the boundaries.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // Start from a power of two at or above the root, then work down to it.
+    let bits = 128 - n.leading_zeros();
+    let mut root = 1u128 << (bits / 2 + 1);
+    loop {
+        let next = (root + n / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// `(g, x, y)` with `a * x + b * y = g`, the greatest common divisor.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// Combines `x ≡ residue (mod modulus)` congruences into the single one
+/// they all hold under. The moduli don't need to be coprime.
+///
+/// Returns `None` if no `x` satisfies them all, or if the combined modulus
+/// doesn't fit in a `u64`.
+pub fn crt(congruences: impl IntoIterator<Item = (u64, u64)>) -> Option<(u64, u64)> {
+    congruences.into_iter().try_fold(
+        (0u64, 1u64),
+        |(residue, modulus), (other_residue, other_modulus)| {
+            let other_residue = other_residue % other_modulus;
+            let divisor = gcd(modulus, other_modulus);
+
+            let difference = other_residue as i128 - residue as i128;
+            if difference % divisor as i128 != 0 {
+                return None;
+            }
+
+            // Solve modulus * k ≡ difference (mod other_modulus) for k.
+            let reduced_modulus = (other_modulus / divisor) as i128;
+            let (_, inverse, _) = extended_gcd(modulus as i128 / divisor as i128, reduced_modulus);
+            let k = ((difference / divisor as i128).rem_euclid(reduced_modulus) as u128
+                * inverse.rem_euclid(reduced_modulus) as u128)
+                % reduced_modulus as u128;
+
+            let combined = u64::try_from(modulus as u128 * reduced_modulus as u128).ok()?;
+            let residue = (residue as u128 + modulus as u128 * k) % combined as u128;
+
+            Some((residue as u64, combined))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, extended_gcd, gcd, isqrt, lcm};
+
+    #[test]
+    fn isqrt_of_small_numbers() {
+        let mut root = 0;
+        for n in 0..100_000u128 {
+            if (root + 1) * (root + 1) <= n {
+                root += 1;
+            }
+            assert_eq!(isqrt(n), root, "isqrt({})", n);
+        }
+    }
+
+    #[test]
+    fn isqrt_at_the_u128_boundaries() {
+        let max_root = u64::MAX as u128;
+
+        assert_eq!(isqrt(u128::MAX), max_root);
+        assert_eq!(isqrt(max_root * max_root), max_root);
+        assert_eq!(isqrt(max_root * max_root - 1), max_root - 1);
+
+        for bits in 1..64 {
+            let root = 1u128 << bits;
+            assert_eq!(isqrt(root * root), root);
+            assert_eq!(isqrt(root * root - 1), root - 1);
+            assert_eq!(isqrt((root + 1) * (root + 1) - 1), root);
+        }
+    }
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(18, 12), 6);
+        assert_eq!(gcd(7, 13), 1);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(5, 0), 5);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(1, 9), 9);
+        assert_eq!(lcm(u32::MAX as u64, u32::MAX as u64 + 2), u64::MAX);
+    }
+
+    #[test]
+    fn extended_gcd_finds_bezout_coefficients() {
+        for (a, b) in [(240, 46), (46, 240), (17, 5), (12, 18), (1, 1), (7, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a as u64, b as u64) as i128, "gcd of {} and {}", a, b);
+            assert_eq!(a * x + b * y, g, "coefficients of {} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn crt_combines_congruences() {
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(crt([(7, 5)]), Some((2, 5)));
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli sharing a factor.
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(3, 6), (3, 9), (0, 1)]), Some((3, 18)));
+        // 1 is odd, 2 is even.
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(0, 3), (1, 3)]), None);
+    }
+
+    #[test]
+    fn crt_matches_brute_force() {
+        for first_modulus in 1..10 {
+            for second_modulus in 1..10 {
+                let combined = lcm(first_modulus, second_modulus);
+                for first in 0..first_modulus {
+                    for second in 0..second_modulus {
+                        let expected = (0..combined)
+                            .find(|x| x % first_modulus == first && x % second_modulus == second)
+                            .map(|x| (x, combined));
+                        assert_eq!(
+                            crt([(first, first_modulus), (second, second_modulus)]),
+                            expected,
+                            "x = {} mod {}, x = {} mod {}",
+                            first,
+                            first_modulus,
+                            second,
+                            second_modulus
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crt_near_the_end_of_u64() {
+        // 2^64 - 1 = (2^32 - 1) * (2^32 + 1), and the two are coprime.
+        let below = u32::MAX as u64;
+        let above = u32::MAX as u64 + 2;
+
+        assert_eq!(
+            crt([(below - 1, below), (above - 1, above)]),
+            Some((u64::MAX - 1, u64::MAX))
+        );
+        assert_eq!(crt([(0, below), (0, above)]), Some((0, u64::MAX)));
+        assert_eq!(crt([(5, u64::MAX), (5, u64::MAX)]), Some((5, u64::MAX)));
+
+        // The combined modulus no longer fits.
+        assert_eq!(crt([(0, u64::MAX), (0, 2)]), None);
+        assert_eq!(crt([(1, above), (1, above + 2)]), None);
+    }
+}
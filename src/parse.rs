@@ -0,0 +1,412 @@
+use crate::error::ErrorKind;
+use crate::PuzzleError;
+
+use anyhow::Result;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The byte offset of `part`, a slice of `whole`, within it.
+fn offset_of(whole: &str, part: &str) -> Option<usize> {
+    (part.as_ptr() as usize)
+        .checked_sub(whole.as_ptr() as usize)
+        .filter(|offset| *offset <= whole.len())
+}
+
+/// A piece of one line of input that remembers where it came from, so that
+/// parsing it can report the line and column of a failure.
+#[derive(Clone, Copy, Debug)]
+pub struct Span<'a> {
+    line: &'a str,
+    text: &'a str,
+    line_number: Option<usize>,
+}
+
+impl<'a> Span<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Span {
+            line,
+            text: line,
+            line_number: None,
+        }
+    }
+
+    /// A span of the whole of `line`, the 1-based `line_number` of the input.
+    pub fn numbered(line: &'a str, line_number: usize) -> Self {
+        Span {
+            line_number: Some(line_number),
+            ..Span::new(line)
+        }
+    }
+
+    fn sub(&self, text: &'a str) -> Self {
+        Span { text, ..*self }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// The 1-based line of the input this span is on, if it is known.
+    pub fn line_number(&self) -> Option<usize> {
+        self.line_number
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The 1-based column this span starts at.
+    pub fn column(&self) -> usize {
+        offset_of(self.line, self.text).map_or(1, |offset| self.line[..offset].chars().count() + 1)
+    }
+
+    /// A malformed input error pointing at the start of this span.
+    pub fn error(&self, message: impl Display) -> PuzzleError {
+        let err = PuzzleError::malformed(message).at_column(self.column());
+        match self.line_number {
+            Some(line) => err.at_line(line),
+            None => err,
+        }
+    }
+
+    pub fn strip_prefix(self, prefix: &str) -> Result<Self, PuzzleError> {
+        self.text
+            .strip_prefix(prefix)
+            .map(|text| self.sub(text))
+            .ok_or_else(|| self.error(format!("Expected \"{}\".", prefix)))
+    }
+
+    pub fn strip_suffix(self, suffix: &str) -> Result<Self, PuzzleError> {
+        self.text
+            .strip_suffix(suffix)
+            .map(|text| self.sub(text))
+            .ok_or_else(|| {
+                self.sub(&self.text[self.text.len()..])
+                    .error(format!("Expected \"{}\".", suffix))
+            })
+    }
+
+    pub fn split_once(self, separator: &str) -> Result<(Self, Self), PuzzleError> {
+        self.text
+            .split_once(separator)
+            .map(|(left, right)| (self.sub(left), self.sub(right)))
+            .ok_or_else(|| self.error(format!("Expected \"{}\".", separator)))
+    }
+
+    pub fn split(self, separator: &'a str) -> impl Iterator<Item = Self> + 'a {
+        self.text.split(separator).map(move |text| self.sub(text))
+    }
+
+    pub fn words(self) -> impl Iterator<Item = Self> + 'a {
+        self.text.split_whitespace().map(move |text| self.sub(text))
+    }
+
+    pub fn chars(self) -> impl Iterator<Item = Self> + 'a {
+        self.text
+            .char_indices()
+            .map(move |(index, char)| self.sub(&self.text[index..index + char.len_utf8()]))
+    }
+
+    /// The longest runs of characters matching `predicate`, like the
+    /// numbers in a line of day 3's schematic.
+    pub fn runs(self, predicate: impl Fn(char) -> bool + 'a) -> impl Iterator<Item = Self> + 'a {
+        self.text
+            .split(move |char: char| !predicate(char))
+            .filter(|run| !run.is_empty())
+            .map(move |run| self.sub(run))
+    }
+
+    pub fn parse<T>(self) -> Result<T, PuzzleError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.text
+            .parse()
+            .map_err(|err| self.error(format!("Could not parse \"{}\": {}", self.text, err)))
+    }
+
+    /// Parses a whitespace separated list like `79 14 55 13`.
+    pub fn numbers<T>(self) -> Result<Vec<T>, PuzzleError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.words().map(Span::parse).collect()
+    }
+
+    /// Parses a list with an explicit separator like `1,1,3`.
+    pub fn list<T>(self, separator: &'a str) -> Result<Vec<T>, PuzzleError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.split(separator).map(Span::parse).collect()
+    }
+
+    /// Parses every character on its own, like the strum-derived tile enums.
+    pub fn cells<T>(self) -> Result<Vec<T>, PuzzleError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.chars().map(Span::parse).collect()
+    }
+
+    /// Parses a `key: values` line like `Time:      7  15   30`.
+    pub fn key_values<T>(self) -> Result<(Self, Vec<T>), PuzzleError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let (key, values) = self.split_once(":")?;
+        Ok((key, values.numbers()?))
+    }
+}
+
+fn parse_numbered_lines<'a, T>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    mut parse_line: impl FnMut(Span<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    lines
+        .map(|(line_number, line)| {
+            parse_line(Span::numbered(line, line_number)).map_err(|err| {
+                let err = PuzzleError::from_anyhow(err, ErrorKind::Malformed);
+                match err.line {
+                    Some(_) => err,
+                    None => err.at_line(line_number),
+                }
+                .into()
+            })
+        })
+        .collect()
+}
+
+/// Whether `line` is blank or a `#` comment, for the files this tool keeps
+/// alongside the inputs.
+pub fn is_comment(line: Span) -> bool {
+    line.as_str().trim().is_empty() || line.as_str().starts_with('#')
+}
+
+/// Parses every line of `input` with `parse_line`, tagging errors with the
+/// line they came from.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    parse_line: impl FnMut(Span<'a>) -> Result<T>,
+) -> Result<Vec<T>> {
+    parse_numbered_lines(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line)),
+        parse_line,
+    )
+}
+
+/// A block of input lines separated from the others by blank lines.
+#[derive(Clone, Copy, Debug)]
+pub struct Section<'a> {
+    text: &'a str,
+    /// 1-based line of the input the section starts on.
+    first_line: usize,
+}
+
+impl<'a> Section<'a> {
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Parses every line of the section, tagging errors with their line in
+    /// the whole input.
+    pub fn lines<T>(self, parse_line: impl FnMut(Span<'a>) -> Result<T>) -> Result<Vec<T>> {
+        parse_numbered_lines(
+            self.text
+                .lines()
+                .enumerate()
+                .map(|(index, line)| (self.first_line + index, line)),
+            parse_line,
+        )
+    }
+
+    /// The only line of a section that must be a single line.
+    pub fn single_line(self) -> Result<Span<'a>, PuzzleError> {
+        let mut lines = self.text.lines();
+        match (lines.next(), lines.next()) {
+            (Some(line), None) => Ok(Span::numbered(line, self.first_line)),
+            _ => {
+                Err(PuzzleError::malformed("Expected a single line.").at_line(self.first_line + 1))
+            }
+        }
+    }
+
+    /// Splits off the first line, for sections that start with a title like
+    /// `seed-to-soil map:`.
+    pub fn split_title(self) -> (Span<'a>, Section<'a>) {
+        let (title, rest) = self.text.split_once('\n').unwrap_or((self.text, ""));
+
+        (
+            Span::numbered(title.trim_end_matches('\r'), self.first_line),
+            Section {
+                text: rest,
+                first_line: self.first_line + 1,
+            },
+        )
+    }
+
+    /// Parses the whole section at once, moving the line of any error from
+    /// the start of the section to the start of the input.
+    pub fn parse<T>(self, parse: impl FnOnce(&'a str) -> Result<T>) -> Result<T> {
+        parse(self.text).map_err(|err| {
+            let err = PuzzleError::from_anyhow(err, ErrorKind::Malformed);
+            let line = self.first_line + err.line.map_or(0, |line| line - 1);
+            err.at_line(line).into()
+        })
+    }
+}
+
+/// Splits `input` on blank lines, like day 5's maps and day 13's patterns.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current: Option<(usize, &str, &str)> = None;
+
+    let mut finish = |current: Option<(usize, &str, &str)>| {
+        if let Some((first_line, first, last)) = current {
+            let start = offset_of(input, first).unwrap_or_default();
+            let end = offset_of(input, last).unwrap_or_default() + last.len();
+            sections.push(Section {
+                text: &input[start..end],
+                first_line,
+            });
+        }
+    };
+
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            finish(current.take());
+        } else {
+            current = match current {
+                Some((first_line, first, _)) => Some((first_line, first, line)),
+                None => Some((index + 1, line, line)),
+            };
+        }
+    }
+    finish(current);
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_lines, sections, Span};
+    use crate::PuzzleError;
+
+    use anyhow::Result;
+
+    fn puzzle_error(err: anyhow::Error) -> PuzzleError {
+        err.downcast().expect("a PuzzleError")
+    }
+
+    #[test]
+    fn column_counts_characters_not_bytes() {
+        let line = "é→ 12 x";
+        let span = Span::numbered(line, 3);
+
+        let words = span.words().collect::<Vec<Span>>();
+        assert_eq!(words[0].column(), 1);
+        assert_eq!(words[1].column(), 4);
+        assert_eq!(words[2].column(), 7);
+
+        let err = words[2].parse::<u32>().unwrap_err();
+        assert_eq!((err.line, err.column), (Some(3), Some(7)));
+    }
+
+    #[test]
+    fn strip_and_split_keep_their_position() {
+        let span = Span::new("Game 12: 3 blue");
+
+        let (game, rest) = span.split_once(": ").unwrap();
+        assert_eq!(game.strip_prefix("Game ").unwrap().column(), 6);
+        assert_eq!(rest.column(), 10);
+
+        let err = rest.strip_suffix(" red").unwrap_err();
+        assert_eq!(err.column, Some(16));
+    }
+
+    #[test]
+    fn runs() {
+        let span = Span::new("467..114.*");
+        let runs = span
+            .runs(|char| char.is_ascii_digit())
+            .map(|run| (run.as_str(), run.column()))
+            .collect::<Vec<(&str, usize)>>();
+        assert_eq!(runs, [("467", 1), ("114", 6)]);
+    }
+
+    #[test]
+    fn parse_lines_tags_errors_with_their_line() {
+        let err = parse_lines("1\n2\nthree\n", |line| Ok(line.parse::<u32>()?)).unwrap_err();
+        let err = puzzle_error(err);
+        assert_eq!((err.line, err.column), (Some(3), Some(1)));
+    }
+
+    #[test]
+    fn sections_split_on_repeated_blank_lines_and_crlf() {
+        let input = "a\r\nb\r\n\r\n\r\n  \r\nc\r\n\r\nd\r\ne\r\n";
+        let sections = sections(input);
+
+        let lines = sections
+            .iter()
+            .map(|section| section.lines(|line| Ok(line.as_str())))
+            .collect::<Result<Vec<Vec<&str>>>>()
+            .unwrap();
+        assert_eq!(lines, [vec!["a", "b"], vec!["c"], vec!["d", "e"]]);
+
+        let (title, rest) = sections[2].split_title();
+        assert_eq!(title.as_str(), "d");
+        assert_eq!(rest.single_line().unwrap().as_str(), "e");
+    }
+
+    #[test]
+    fn section_lines_are_numbered_from_the_whole_input() {
+        let input = "seeds: 1\n\n\ntitle:\n1 2\n3 x\n";
+        let sections = sections(input);
+
+        let err = sections[1]
+            .lines(|line| Ok(line.numbers::<u32>()?))
+            .unwrap_err();
+        let err = puzzle_error(err);
+        assert_eq!((err.line, err.column), (Some(4), Some(1)));
+
+        let (title, rest) = sections[1].split_title();
+        assert_eq!(title.line_number(), Some(4));
+        let err = rest.lines(|line| Ok(line.numbers::<u32>()?)).unwrap_err();
+        let err = puzzle_error(err);
+        assert_eq!((err.line, err.column), (Some(6), Some(3)));
+    }
+
+    #[test]
+    fn section_parse_moves_errors_to_the_whole_input() {
+        let input = "first\n\nsecond\nthird\n";
+        let sections = sections(input);
+
+        let err = sections[1]
+            .parse(|text| parse_lines(text, |line| Ok(line.parse::<u32>()?)))
+            .unwrap_err();
+        assert_eq!(puzzle_error(err).line, Some(3));
+
+        let err = sections[1]
+            .parse(|text| {
+                parse_lines(text, |line| match line.as_str() {
+                    "third" => Err(line.error("Bad line.").into()),
+                    _ => Ok(()),
+                })
+            })
+            .unwrap_err();
+        assert_eq!(puzzle_error(err).line, Some(4));
+
+        let err = sections[0]
+            .parse(|_| -> Result<()> { Err(PuzzleError::malformed("No line.").into()) })
+            .unwrap_err();
+        assert_eq!(puzzle_error(err).line, Some(1));
+    }
+}
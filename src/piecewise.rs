@@ -0,0 +1,245 @@
+use crate::interval::{Interval, IntervalSet};
+
+use std::fmt::{self, Display, Formatter};
+
+/// A function on the integers that adds a fixed offset on each of a set of
+/// intervals and leaves everything else alone, like the almanac's maps.
+///
+/// Segments are sorted, disjoint and never have an offset of zero, so they
+/// can be binary searched and every function has one representation.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Piecewise {
+    segments: Vec<(Interval, i64)>,
+}
+
+impl Piecewise {
+    /// The function that leaves every value alone.
+    pub fn identity() -> Self {
+        Piecewise::default()
+    }
+
+    /// Builds a function from `(interval, offset)` pieces. Where pieces
+    /// overlap, the first one a value falls in applies.
+    pub fn from_pieces(pieces: impl IntoIterator<Item = (Interval, i64)>) -> Self {
+        let mut covered = IntervalSet::new();
+        let mut segments = Vec::new();
+
+        for (interval, offset) in pieces {
+            let uncovered = IntervalSet::from(interval).difference(&covered);
+            segments.extend(uncovered.iter().map(|part| (*part, offset)));
+            covered.insert(interval);
+        }
+
+        Piecewise::normalize(segments)
+    }
+
+    /// Sorts disjoint segments, dropping identity ones and joining
+    /// neighbours with the same offset.
+    fn normalize(mut segments: Vec<(Interval, i64)>) -> Self {
+        segments.retain(|(interval, offset)| *offset != 0 && !interval.is_empty());
+        segments.sort();
+
+        let mut joined: Vec<(Interval, i64)> = Vec::with_capacity(segments.len());
+        for (interval, offset) in segments {
+            match joined.last_mut() {
+                Some((last, last_offset))
+                    if last.end == interval.start && *last_offset == offset =>
+                {
+                    last.end = interval.end
+                }
+                _ => joined.push((interval, offset)),
+            }
+        }
+
+        Piecewise { segments: joined }
+    }
+
+    /// The segments that move values, in order.
+    pub fn segments(&self) -> &[(Interval, i64)] {
+        &self.segments
+    }
+
+    /// The index of the first segment that doesn't end at or before `value`.
+    fn first_segment_from(&self, value: i64) -> usize {
+        self.segments
+            .partition_point(|(interval, _)| interval.end <= value)
+    }
+
+    pub fn apply(&self, value: i64) -> i64 {
+        match self.segments.get(self.first_segment_from(value)) {
+            Some((interval, offset)) if interval.contains(value) => value + offset,
+            _ => value,
+        }
+    }
+
+    /// Splits `interval` into the parts each offset applies to, gaps between
+    /// segments included with an offset of zero.
+    fn split(&self, interval: Interval) -> Vec<(Interval, i64)> {
+        let mut parts = Vec::new();
+        let mut start = interval.start;
+
+        for (segment, offset) in &self.segments[self.first_segment_from(interval.start)..] {
+            if segment.start >= interval.end {
+                break;
+            }
+            if segment.start > start {
+                parts.push((Interval::new(start, segment.start), 0));
+            }
+            if let Some(part) = segment.intersection(&interval) {
+                parts.push((part, *offset));
+            }
+            start = segment.end;
+        }
+
+        if start < interval.end {
+            parts.push((Interval::new(start, interval.end), 0));
+        }
+
+        parts
+    }
+
+    /// Every value of `inputs` put through the function.
+    pub fn apply_set(&self, inputs: &IntervalSet) -> IntervalSet {
+        inputs
+            .iter()
+            .flat_map(|interval| self.split(*interval))
+            .map(|(part, offset)| part.shift(offset))
+            .collect()
+    }
+
+    /// The whole number line as `(interval, offset)` pieces, the gaps between
+    /// segments included with an offset of zero.
+    fn pieces(&self) -> Vec<(Interval, i64)> {
+        self.split(Interval::new(i64::MIN, i64::MAX))
+    }
+
+    /// The function that applies this one and then `next`.
+    pub fn then(&self, next: &Piecewise) -> Piecewise {
+        let segments = self
+            .pieces()
+            .into_iter()
+            .flat_map(|(domain, offset)| {
+                next.split(domain.shift(offset))
+                    .into_iter()
+                    .map(move |(part, next_offset)| (part.shift(-offset), offset + next_offset))
+            })
+            .collect();
+
+        Piecewise::normalize(segments)
+    }
+
+    /// The function undoing this one, if every value comes from exactly one
+    /// other value.
+    pub fn inverse(&self) -> Option<Piecewise> {
+        let mut images = self
+            .pieces()
+            .into_iter()
+            .map(|(domain, offset)| (domain.shift(offset), -offset))
+            .collect::<Vec<(Interval, i64)>>();
+        images.sort();
+
+        let mut end = i64::MIN;
+        for (image, _) in &images {
+            if image.start != end {
+                return None;
+            }
+            end = image.end;
+        }
+
+        (end == i64::MAX).then(|| Piecewise::normalize(images))
+    }
+}
+
+/// One segment per line, like `79..93 +2`.
+impl Display for Piecewise {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let segments = self
+            .segments
+            .iter()
+            .map(|(interval, offset)| format!("{}..{} {:+}", interval.start, interval.end, offset))
+            .collect::<Vec<String>>();
+        write!(f, "{}", segments.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Piecewise;
+    use crate::interval::{Interval, IntervalSet};
+
+    fn piecewise(pieces: &[(i64, i64, i64)]) -> Piecewise {
+        Piecewise::from_pieces(
+            pieces
+                .iter()
+                .map(|&(start, end, offset)| (Interval::new(start, end), offset)),
+        )
+    }
+
+    #[test]
+    fn first_piece_wins() {
+        let f = piecewise(&[(0, 5, 10), (3, 8, 20)]);
+        assert_eq!(f.apply(-1), -1);
+        assert_eq!(f.apply(4), 14);
+        assert_eq!(f.apply(5), 25);
+        assert_eq!(f.apply(8), 8);
+        assert_eq!(f.to_string(), "0..5 +10\n5..8 +20");
+    }
+
+    #[test]
+    fn then_applies_one_after_the_other() {
+        let f = piecewise(&[(0, 10, 5), (10, 15, -10), (20, 30, 1)]);
+        let g = piecewise(&[(3, 12, -3), (12, 18, 100), (25, 26, -25)]);
+
+        for (first, second) in [(&f, &g), (&g, &f), (&f, &f)] {
+            let composed = first.then(second);
+            for x in -5..40 {
+                assert_eq!(composed.apply(x), second.apply(first.apply(x)), "at {}", x);
+            }
+        }
+
+        assert_eq!(f.then(&Piecewise::identity()), f);
+        assert_eq!(Piecewise::identity().then(&f), f);
+    }
+
+    #[test]
+    fn apply_set_matches_apply() {
+        let f = piecewise(&[(0, 10, 5), (10, 15, -10), (20, 30, 1)]);
+        let inputs: IntervalSet = [Interval::new(-3, 12), Interval::new(14, 22)]
+            .into_iter()
+            .collect();
+
+        let mut expected = (-3..12)
+            .chain(14..22)
+            .map(|x| f.apply(x))
+            .collect::<Vec<_>>();
+        expected.sort();
+        expected.dedup();
+
+        let outputs = f.apply_set(&inputs);
+        let actual = (-20..40)
+            .filter(|&x| outputs.contains(x))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn inverts_bijections() {
+        // Swaps 0..5 with 10..15.
+        let f = piecewise(&[(0, 5, 10), (10, 15, -10)]);
+        let inverse = f.inverse().expect("a bijection");
+        for x in -5..20 {
+            assert_eq!(inverse.apply(f.apply(x)), x);
+            assert_eq!(f.apply(inverse.apply(x)), x);
+        }
+
+        assert_eq!(Piecewise::identity().inverse(), Some(Piecewise::identity()));
+    }
+
+    #[test]
+    fn rejects_non_bijections() {
+        // 0..5 lands on 10..15, which is also left where it is.
+        assert_eq!(piecewise(&[(0, 5, 10)]).inverse(), None);
+        // 0..5 moves away and nothing takes its place.
+        assert_eq!(piecewise(&[(0, 5, 100), (100, 105, -50)]).inverse(), None);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::answers::Verdict;
+use crate::{Answer, Part, PuzzleError};
+
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How the runner prints reports.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    /// A single JSON document once every part has finished.
+    Json,
+    /// One JSON object per line as each part finishes.
+    JsonLines,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(format: &str) -> Result<Self> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::JsonLines),
+            format => Err(anyhow!(
+                "Invalid format \"{}\", expected text, json or jsonl.",
+                format
+            )),
+        }
+    }
+}
+
+/// Quotes and escapes `text` as a JSON string.
+pub fn json_string(text: &str) -> String {
+    let mut json = String::from('"');
+    text.chars().for_each(|char| match char {
+        '"' => json.push_str("\\\""),
+        '\\' => json.push_str("\\\\"),
+        '\n' => json.push_str("\\n"),
+        '\r' => json.push_str("\\r"),
+        '\t' => json.push_str("\\t"),
+        char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+        char => json.push(char),
+    });
+    json.push('"');
+    json
+}
+
+fn json_option<T: ToString>(value: Option<T>) -> String {
+    value.map_or(String::from("null"), |value| value.to_string())
+}
+
+/// The outcome of running one part of one day.
+#[derive(Debug)]
+pub struct PartReport {
+    pub day: u32,
+    pub part: Part,
+    pub answer: Result<Answer, PuzzleError>,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+impl PartReport {
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.solve_time
+    }
+
+    pub fn print(&self) {
+        let timing = format!(
+            "(parse {:.2?}, solve {:.2?})",
+            self.parse_time, self.solve_time
+        );
+
+        match &self.answer {
+            Ok(answer) if answer.is_multiline() => {
+                println!("\tPart {} - {}", self.part, timing);
+                answer
+                    .to_string()
+                    .lines()
+                    .for_each(|line| println!("\t\t{}", line));
+            }
+            Ok(answer) => println!("\tPart {} - {} {}", self.part, answer, timing),
+            Err(err) => println!("Part {} Error - {}", self.part, err),
+        }
+    }
+
+    /// Renders the report as a single line JSON object, including the
+    /// verdict when answers were verified.
+    ///
+    /// Answers are strings so that large integers survive JSON parsers.
+    pub fn to_json(&self, verdict: Option<&Verdict>) -> String {
+        let (status, answer, error) = match &self.answer {
+            Ok(answer) => ("ok", json_string(&answer.to_string()), String::from("null")),
+            Err(err) => (
+                "error",
+                String::from("null"),
+                format!(
+                    "{{\"kind\": {}, \"message\": {}, \"line\": {}, \"column\": {}}}",
+                    json_string(err.kind.id()),
+                    json_string(&err.message),
+                    json_option(err.line),
+                    json_option(err.column)
+                ),
+            ),
+        };
+
+        let verdict = match verdict {
+            Some(Verdict::Pass) => String::from(", \"verdict\": \"pass\""),
+            Some(Verdict::Fail { expected }) => format!(
+                ", \"verdict\": \"fail\", \"expected\": {}",
+                json_string(expected)
+            ),
+            Some(Verdict::Unknown) => String::from(", \"verdict\": \"unknown\""),
+            None => String::new(),
+        };
+
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"status\": \"{}\", \"answer\": {}, \"error\": {}, \"parse_ns\": {}, \"solve_ns\": {}, \"total_ns\": {}{}}}",
+            self.day,
+            self.part,
+            status,
+            answer,
+            error,
+            self.parse_time.as_nanos(),
+            self.solve_time.as_nanos(),
+            self.total_time().as_nanos(),
+            verdict
+        )
+    }
+
+    fn table_answer(&self) -> String {
+        match &self.answer {
+            Ok(Answer::Grid(rows)) => format!("<{} rows>", rows.len()),
+            Ok(answer) => answer.to_string(),
+            Err(_) => String::from("error"),
+        }
+    }
+}
+
+/// Renders `rows` under `headers` with every column aligned, numeric columns
+/// to the right and the `left_aligned` ones to the left, and an optional
+/// `footer` row set apart by a separator.
+pub fn render_table(
+    headers: &[String],
+    rows: &[Vec<String>],
+    footer: Option<&[String]>,
+    left_aligned: &[usize],
+) -> String {
+    let widths = rows
+        .iter()
+        .map(Vec::as_slice)
+        .chain([headers])
+        .chain(footer)
+        .fold(vec![0; headers.len()], |mut widths, row| {
+            widths
+                .iter_mut()
+                .zip(row.iter())
+                .for_each(|(width, cell)| *width = (*width).max(cell.chars().count()));
+            widths
+        });
+
+    let format_row = |row: &[String]| {
+        row.iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(index, (cell, width))| {
+                if left_aligned.contains(&index) {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+
+    let separator = "-".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1));
+
+    let mut table = vec![format_row(headers), separator.clone()];
+    table.extend(rows.iter().map(|row| format_row(row)));
+    if let Some(footer) = footer {
+        table.push(separator);
+        table.push(format_row(footer));
+    }
+
+    table.join("\n")
+}
+
+/// Renders an aligned table of every report followed by the total time.
+pub fn summary_table(reports: &[PartReport]) -> String {
+    let headers = ["Day", "Part", "Answer", "Parse", "Solve", "Total"].map(String::from);
+
+    let total = reports.iter().map(PartReport::total_time).sum::<Duration>();
+    let total = [
+        String::from("Total"),
+        String::new(),
+        String::new(),
+        String::new(),
+        String::new(),
+        format!("{:.2?}", total),
+    ];
+
+    let rows = reports
+        .iter()
+        .map(|report| {
+            vec![
+                report.day.to_string(),
+                report.part.to_string(),
+                report.table_answer(),
+                format!("{:.2?}", report.parse_time),
+                format!("{:.2?}", report.solve_time),
+                format!("{:.2?}", report.total_time()),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+
+    // Answers read better left aligned, everything else is numeric.
+    render_table(&headers, &rows, Some(&total), &[2])
+}
+
+/// Renders every report as one JSON document, with `verdicts` in the same
+/// order as `reports` when answers were verified.
+pub fn json_document(
+    reports: &[PartReport],
+    verdicts: &[Option<Verdict>],
+    wall_time: Duration,
+) -> String {
+    let total = reports.iter().map(PartReport::total_time).sum::<Duration>();
+    let reports = reports
+        .iter()
+        .enumerate()
+        .map(|(index, report)| {
+            let verdict = verdicts.get(index).and_then(Option::as_ref);
+            format!("    {}", report.to_json(verdict))
+        })
+        .collect::<Vec<String>>();
+
+    format!(
+        "{{\n  \"reports\": [\n{}\n  ],\n  \"total_ns\": {},\n  \"wall_ns\": {}\n}}",
+        reports.join(",\n"),
+        total.as_nanos(),
+        wall_time.as_nanos()
+    )
+}
@@ -0,0 +1,169 @@
+use crate::{Part, PartReport, PuzzleError, Solver, Tracer};
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::Instant;
+
+/// One part of one day, ready to run.
+#[derive(Clone, Copy)]
+pub struct Task<'a> {
+    pub solver: &'a dyn Solver,
+    pub part: Part,
+    pub input: &'a str,
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+/// Runs one task, turning a panicking solver into a failed report so the
+/// other tasks still finish.
+fn run_task(task: &Task, tracer: &Tracer) -> PartReport {
+    let start = Instant::now();
+
+    catch_unwind(AssertUnwindSafe(|| {
+        task.solver.run_part(task.part, task.input, tracer)
+    }))
+    .unwrap_or_else(|payload| PartReport {
+        day: task.solver.day(),
+        part: task.part,
+        answer: Err(PuzzleError {
+            day: Some(task.solver.day()),
+            part: Some(task.part),
+            ..PuzzleError::invariant(format!(
+                "The solver panicked: {}",
+                panic_message(payload.as_ref())
+            ))
+        }),
+        parse_time: Default::default(),
+        solve_time: start.elapsed(),
+    })
+}
+
+/// Runs `tasks` on up to `jobs` worker threads.
+///
+/// Each task is timed on its own worker, and `on_report` is called on the
+/// calling thread in task order as soon as every earlier task has finished.
+/// A task that panics is reported as an invariant error.
+pub fn run_tasks(
+    tasks: &[Task],
+    jobs: usize,
+    tracer: &Tracer,
+    mut on_report: impl FnMut(&PartReport),
+) -> Vec<PartReport> {
+    let next_task = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, tasks.len().max(1)) {
+            let sender = sender.clone();
+            let next_task = &next_task;
+            scope.spawn(move || loop {
+                let index = next_task.fetch_add(1, Ordering::Relaxed);
+                let Some(task) = tasks.get(index) else {
+                    break;
+                };
+
+                let report = run_task(task, tracer);
+                if sender.send((index, report)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<PartReport>> = tasks.iter().map(|_| None).collect();
+        let mut reported = 0;
+        for (index, report) in receiver {
+            finished[index] = Some(report);
+
+            while let Some(Some(report)) = finished.get(reported) {
+                on_report(report);
+                reported += 1;
+            }
+        }
+
+        finished.into_iter().flatten().collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_tasks, Task};
+    use crate::error::ErrorKind;
+    use crate::{Answer, Part, Solvable, Trace, Tracer};
+
+    use anyhow::Result;
+
+    /// Answers with the length of its input, or panics on `panic`.
+    struct Fragile {}
+
+    impl Solvable for Fragile {
+        type Parsed = String;
+
+        fn get_day() -> u32 {
+            99
+        }
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            if input == "panic" {
+                panic!("the input is {}", input);
+            }
+            Ok(input.to_string())
+        }
+
+        fn solve_part_one(parsed: Self::Parsed, _: &Trace) -> Result<Answer> {
+            Ok(parsed.len().into())
+        }
+
+        fn solve_part_two(_: Self::Parsed, _: &Trace) -> Result<Answer> {
+            panic!("part two is unsolved");
+        }
+    }
+
+    #[test]
+    fn reports_panics_in_order() {
+        let tasks = [
+            ("a", Part::One),
+            ("panic", Part::One),
+            ("abc", Part::One),
+            ("ab", Part::Two),
+            ("abcd", Part::One),
+        ]
+        .map(|(input, part)| Task {
+            solver: &Fragile {},
+            part,
+            input,
+        });
+
+        for jobs in [1, 3] {
+            let mut seen = Vec::new();
+            let reports = run_tasks(&tasks, jobs, &Tracer::default(), |report| {
+                seen.push(report.answer.as_ref().ok().map(Answer::to_string))
+            });
+
+            let answers = reports
+                .iter()
+                .map(|report| report.answer.as_ref().ok().map(Answer::to_string))
+                .collect::<Vec<_>>();
+            let some = |answer: &str| Some(answer.to_string());
+            assert_eq!(answers, [some("1"), None, some("3"), None, some("4")]);
+            assert_eq!(seen, answers);
+
+            let err = reports[1].answer.as_ref().unwrap_err();
+            assert_eq!(err.kind, ErrorKind::Invariant);
+            assert_eq!((err.day, err.part), (Some(99), Some(Part::One)));
+            assert_eq!(err.message, "The solver panicked: the input is panic");
+
+            let err = reports[3].answer.as_ref().unwrap_err();
+            assert_eq!(err.message, "The solver panicked: part two is unsolved");
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use anyhow::{bail, Context, Result};
+use std::fs::{read_to_string, remove_file, write, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_SOURCE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+
+/// The module generated for a new day, with `{day}` standing in for its number.
+const TEMPLATE: &str = r#"use crate::trace::{trace, Trace};
+use crate::{Answer, Solvable};
+
+use anyhow::{bail, Result};
+
+pub struct Day{day} {}
+
+impl Solvable for Day{day} {
+    type Parsed = Vec<String>;
+
+    fn get_day() -> u32 {
+        {day}
+    }
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn solve_part_one(lines: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:#?}", lines);
+
+        bail!("Part one is not solved yet.")
+    }
+
+    fn solve_part_two(lines: Self::Parsed, trace: &Trace) -> Result<Answer> {
+        trace!(trace, Verbose, "{:#?}", lines);
+
+        bail!("Part two is not solved yet.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day{day};
+    use crate::testing::example_tests;
+
+    const EXAMPLE: &str = "\
+";
+
+    example_tests! {
+        Day{day},
+        #[ignore = "Example not filled in yet"]
+        part_one: [(EXAMPLE, 0)],
+        #[ignore = "Example not filled in yet"]
+        part_two: [(EXAMPLE, 0)],
+    }
+}
+"#;
+
+/// Generates `days/day{N}.rs` under `source_dir`, declares and registers it in
+/// `days/mod.rs` and creates an empty `inputs/day{N}.txt`.
+///
+/// Refuses to touch anything if the day already has a module or `days/mod.rs`
+/// can't be updated. Returns the files that were created or changed.
+pub fn new_day(source_dir: &Path, day: u32) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("Day {} is not a puzzle day, expected 1 to 25.", day);
+    }
+
+    let module_path = source_dir.join(format!("days/day{}.rs", day));
+    let mod_path = source_dir.join("days/mod.rs");
+    let input_path = source_dir.join(format!("inputs/day{}.txt", day));
+
+    if module_path.exists() {
+        bail!("Day {} already exists at {}.", day, module_path.display());
+    }
+
+    let mod_file = read_to_string(&mod_path)
+        .with_context(|| format!("Could not read {}.", mod_path.display()))?;
+    let mod_file = register(&mod_file, day)?;
+
+    let module = TEMPLATE.replace("{day}", &day.to_string());
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&module_path)
+        .and_then(|mut file| file.write_all(module.as_bytes()))
+        .with_context(|| format!("Could not write {}.", module_path.display()))?;
+    if let Err(err) = write(&mod_path, mod_file) {
+        // Don't leave a module behind that nothing declares.
+        let _ = remove_file(&module_path);
+        return Err(err).with_context(|| format!("Could not write {}.", mod_path.display()));
+    }
+
+    let mut changed = vec![module_path, mod_path];
+
+    // Keep any input that was downloaded ahead of time.
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&input_path)
+    {
+        Ok(_) => changed.push(input_path),
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => (),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Could not create {}.", input_path.display()))
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Adds the `pub mod`, `pub use` and `REGISTRY` lines for `day` to the
+/// contents of `days/mod.rs`, keeping each group in the order rustfmt and the
+/// registry expect.
+fn register(mod_file: &str, day: u32) -> Result<String> {
+    let module = format!("day{}", day);
+    let mod_line = format!("pub mod {};", module);
+    let use_line = format!("pub use {}::Day{};", module, day);
+    let registry_line = format!("    &Day{} {{}},", day);
+
+    let mut lines = mod_file.lines().map(String::from).collect::<Vec<String>>();
+    if lines
+        .iter()
+        .any(|line| [&mod_line, &use_line, &registry_line].contains(&line))
+    {
+        bail!("Day {} is already declared in days/mod.rs.", day);
+    }
+
+    // rustfmt sorts the declarations by module name, the registry is by day.
+    insert_sorted(&mut lines, "pub mod ", mod_line)?;
+    insert_sorted(&mut lines, "pub use ", use_line)?;
+
+    let registry_day = |line: &str| {
+        line.trim()
+            .trim_start_matches("&Day")
+            .trim_end_matches(" {},")
+            .parse::<u32>()
+            .unwrap_or(u32::MAX)
+    };
+    let start = lines
+        .iter()
+        .position(|line| line.contains("REGISTRY"))
+        .context("Could not find REGISTRY in days/mod.rs.")?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line.trim() == "];")
+            .context("Could not find the end of REGISTRY in days/mod.rs.")?;
+    let index = (start + 1..end)
+        .find(|index| registry_day(&lines[*index]) > day)
+        .unwrap_or(end);
+    lines.insert(index, registry_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Inserts `line` into the run of lines starting with `prefix`, ordered by the
+/// module name that follows the prefix.
+fn insert_sorted(lines: &mut Vec<String>, prefix: &str, line: String) -> Result<()> {
+    let key = |line: &str| {
+        line.trim_start_matches(prefix)
+            .split([':', ';'])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let start = lines
+        .iter()
+        .position(|existing| existing.starts_with(prefix))
+        .with_context(|| format!("Could not find any \"{}\" lines in days/mod.rs.", prefix))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .take_while(|existing| existing.starts_with(prefix))
+            .count();
+    let index = (start..end)
+        .find(|index| key(&lines[*index]) > key(&line))
+        .unwrap_or(end);
+    lines.insert(index, line);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{new_day, register};
+
+    use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+    use std::path::PathBuf;
+    use std::process;
+
+    const MOD_FILE: &str = "\
+pub mod day1;
+pub mod day10;
+pub mod day3;
+
+pub use day1::Day1;
+pub use day10::Day10;
+pub use day3::Day3;
+
+use crate::Solver;
+
+/// Every implemented day, in day order.
+pub static REGISTRY: &[&dyn Solver] = &[
+    &Day1 {},
+    &Day3 {},
+    &Day10 {},
+];
+";
+
+    /// A throwaway source tree holding `mod_file` as `days/mod.rs`.
+    fn source_dir(name: &str, mod_file: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("my-project-{}-{}", process::id(), name));
+        let _ = remove_dir_all(&dir);
+        create_dir_all(dir.join("days")).unwrap();
+        create_dir_all(dir.join("inputs")).unwrap();
+        write(dir.join("days/mod.rs"), mod_file).unwrap();
+        dir
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let registered = register(MOD_FILE, 2).unwrap();
+        let registered = register(&registered, 25).unwrap();
+
+        assert_eq!(
+            registered,
+            "\
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day25;
+pub mod day3;
+
+pub use day1::Day1;
+pub use day10::Day10;
+pub use day2::Day2;
+pub use day25::Day25;
+pub use day3::Day3;
+
+use crate::Solver;
+
+/// Every implemented day, in day order.
+pub static REGISTRY: &[&dyn Solver] = &[
+    &Day1 {},
+    &Day2 {},
+    &Day3 {},
+    &Day10 {},
+    &Day25 {},
+];
+"
+        );
+    }
+
+    #[test]
+    fn registers_day_before_the_others() {
+        let mod_file = "\
+pub mod day3;
+
+pub use day3::Day3;
+
+pub static REGISTRY: &[&dyn Solver] = &[
+    &Day3 {},
+];
+";
+
+        assert_eq!(
+            register(mod_file, 1).unwrap(),
+            "\
+pub mod day1;
+pub mod day3;
+
+pub use day1::Day1;
+pub use day3::Day3;
+
+pub static REGISTRY: &[&dyn Solver] = &[
+    &Day1 {},
+    &Day3 {},
+];
+"
+        );
+    }
+
+    #[test]
+    fn refuses_registered_days() {
+        for day in [1, 3, 10] {
+            assert_eq!(
+                register(MOD_FILE, day).unwrap_err().to_string(),
+                format!("Day {} is already declared in days/mod.rs.", day)
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_unrecognised_mod_files() {
+        let err = register("pub mod day1;\npub use day1::Day1;\n", 2).unwrap_err();
+        assert_eq!(err.to_string(), "Could not find REGISTRY in days/mod.rs.");
+
+        let err = register("pub use day1::Day1;\n", 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Could not find any \"pub mod \" lines in days/mod.rs."
+        );
+    }
+
+    #[test]
+    fn creates_new_day() {
+        let dir = source_dir("scaffold-new", MOD_FILE);
+
+        let changed = new_day(&dir, 7).unwrap();
+        assert_eq!(
+            changed,
+            [
+                dir.join("days/day7.rs"),
+                dir.join("days/mod.rs"),
+                dir.join("inputs/day7.txt"),
+            ]
+        );
+        let module = read_to_string(dir.join("days/day7.rs")).unwrap();
+        assert!(module.contains("impl Solvable for Day7 {"));
+        assert!(!module.contains("{day}"));
+        assert_eq!(
+            read_to_string(dir.join("days/mod.rs")).unwrap(),
+            register(MOD_FILE, 7).unwrap()
+        );
+
+        let err = new_day(&dir, 7).unwrap_err();
+        assert!(err.to_string().starts_with("Day 7 already exists"));
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_existing_input() {
+        let dir = source_dir("scaffold-input", MOD_FILE);
+        write(dir.join("inputs/day7.txt"), "downloaded").unwrap();
+
+        let changed = new_day(&dir, 7).unwrap();
+        assert!(!changed.contains(&dir.join("inputs/day7.txt")));
+        assert_eq!(
+            read_to_string(dir.join("inputs/day7.txt")).unwrap(),
+            "downloaded"
+        );
+
+        remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn writes_nothing_when_registering_fails() {
+        let dir = source_dir("scaffold-refused", MOD_FILE);
+
+        assert!(new_day(&dir, 3).is_err());
+        assert!(new_day(&dir, 0).is_err());
+        assert!(new_day(&dir, 26).is_err());
+        assert!(!dir.join("days/day3.rs").exists());
+        assert!(!dir.join("inputs/day3.txt").exists());
+        assert_eq!(read_to_string(dir.join("days/mod.rs")).unwrap(), MOD_FILE);
+
+        remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{Answer, Part, Solver, Tracer};
+
+/// Runs every `(input, expected)` example of `part` through the same path as
+/// the runner, panicking on the first error or wrong answer.
+pub fn check_examples(solver: &dyn Solver, part: Part, examples: &[(&str, Answer)]) {
+    examples
+        .iter()
+        .enumerate()
+        .for_each(|(index, (input, expected))| {
+            match solver.run_part(part, input, &Tracer::default()).answer {
+                Ok(answer) => assert_eq!(
+                    answer,
+                    *expected,
+                    "Day {} part {} example {}",
+                    solver.day(),
+                    part,
+                    index + 1
+                ),
+                Err(err) => panic!(
+                    "Day {} part {} example {} failed: {:?}",
+                    solver.day(),
+                    part,
+                    index + 1,
+                    err
+                ),
+            }
+        });
+}
+
+/// Generates a `part_one` and `part_two` test for a day from its examples.
+///
+/// ```ignore
+/// example_tests! {
+///     Day1,
+///     part_one: [(EXAMPLE, 142)],
+///     #[ignore = "reason"]
+///     part_two: [(EXAMPLE_TWO, 281)],
+/// }
+/// ```
+macro_rules! example_tests {
+    (
+        $day:ident,
+        $(#[$one_attr:meta])*
+        part_one: [$(($one_input:expr, $one_expected:expr)),* $(,)?],
+        $(#[$two_attr:meta])*
+        part_two: [$(($two_input:expr, $two_expected:expr)),* $(,)?] $(,)?
+    ) => {
+        #[test]
+        $(#[$one_attr])*
+        fn part_one() {
+            $crate::testing::check_examples(
+                &$day {},
+                $crate::Part::One,
+                &[$(($one_input, $crate::Answer::from($one_expected))),*],
+            );
+        }
+
+        #[test]
+        $(#[$two_attr])*
+        fn part_two() {
+            $crate::testing::check_examples(
+                &$day {},
+                $crate::Part::Two,
+                &[$(($two_input, $crate::Answer::from($two_expected))),*],
+            );
+        }
+    };
+}
+
+pub(crate) use example_tests;
@@ -0,0 +1,173 @@
+use crate::Part;
+
+use anyhow::{anyhow, Context, Result};
+use std::fmt::{self, Arguments, Display, Formatter};
+use std::fs::File;
+use std::io::{stderr, stdout, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// How much a solver traces, each level including the ones before it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    /// A few lines per part, such as intermediate totals.
+    Summary,
+    /// A line per step of the solution.
+    Step,
+    /// Whole data structures.
+    Verbose,
+}
+
+impl FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(level: &str) -> Result<Self> {
+        match level {
+            "summary" => Ok(Level::Summary),
+            "step" => Ok(Level::Step),
+            "verbose" => Ok(Level::Verbose),
+            level => Err(anyhow!(
+                "Invalid trace level \"{}\", expected summary, step or verbose.",
+                level
+            )),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Summary => write!(f, "summary"),
+            Level::Step => write!(f, "step"),
+            Level::Verbose => write!(f, "verbose"),
+        }
+    }
+}
+
+/// Where trace lines are written.
+#[derive(Default)]
+enum Sink {
+    #[default]
+    Stdout,
+    Stderr,
+    File(Mutex<BufWriter<File>>),
+}
+
+/// Where trace output goes and which days produce it.
+///
+/// The default tracer is off.
+#[derive(Default)]
+pub struct Tracer {
+    level: Option<Level>,
+    days: Option<Vec<u32>>,
+    sink: Sink,
+}
+
+impl Tracer {
+    pub fn new(level: Level) -> Self {
+        Tracer {
+            level: Some(level),
+            ..Self::default()
+        }
+    }
+
+    /// Only trace the given days.
+    pub fn with_days(mut self, days: Vec<u32>) -> Self {
+        self.days = Some(days);
+        self
+    }
+
+    pub fn with_file(mut self, path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Could not create trace file {}.", path.display()))?;
+        self.sink = Sink::File(Mutex::new(BufWriter::new(file)));
+        Ok(self)
+    }
+
+    /// Writes to stderr rather than stdout, to keep stdout machine readable.
+    pub fn with_stderr(mut self) -> Self {
+        self.sink = Sink::Stderr;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.level.is_some()
+    }
+
+    /// The trace handed to the solver of one part.
+    pub fn for_part(&self, day: u32, part: Part) -> Trace<'_> {
+        let traced = match &self.days {
+            Some(days) => days.contains(&day),
+            None => true,
+        };
+
+        Trace {
+            tracer: self,
+            level: self.level.filter(|_| traced),
+            day,
+            part,
+        }
+    }
+
+    fn write(&self, day: u32, part: Part, args: Arguments) {
+        let message = args.to_string();
+        let lines = message
+            .split('\n')
+            .map(|line| format!("[day {} part {}] {}\n", day, part, line))
+            .collect::<String>();
+
+        // Tracing is best effort, a failed write should not fail the part.
+        match &self.sink {
+            Sink::Stdout => {
+                let _ = stdout().lock().write_all(lines.as_bytes());
+            }
+            Sink::Stderr => {
+                let _ = stderr().lock().write_all(lines.as_bytes());
+            }
+            Sink::File(file) => {
+                if let Ok(mut file) = file.lock() {
+                    let _ = file.write_all(lines.as_bytes());
+                    let _ = file.flush();
+                }
+            }
+        }
+    }
+}
+
+/// The tracing handle solvers receive, scoped to one day and part.
+///
+/// Use it through the `trace!` macro so that arguments are only formatted
+/// when the level is enabled.
+#[derive(Clone, Copy)]
+pub struct Trace<'a> {
+    tracer: &'a Tracer,
+    level: Option<Level>,
+    day: u32,
+    part: Part,
+}
+
+impl Trace<'_> {
+    pub fn enabled(&self, level: Level) -> bool {
+        self.level.is_some_and(|enabled| level <= enabled)
+    }
+
+    pub fn write(&self, args: Arguments) {
+        self.tracer.write(self.day, self.part, args);
+    }
+}
+
+/// Writes a formatted line to `$trace` if `$level` is enabled.
+///
+/// ```ignore
+/// trace!(trace, Step, "Seed {} maps to {}", seed, location);
+/// ```
+macro_rules! trace {
+    ($trace:expr, $level:ident, $($arg:tt)*) => {
+        if $trace.enabled($crate::trace::Level::$level) {
+            $trace.write(format_args!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use trace;
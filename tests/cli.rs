@@ -0,0 +1,59 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const DAY6_EXAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_my-project"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary starts");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("the input is written");
+
+    child.wait_with_output().expect("the binary finishes")
+}
+
+#[test]
+fn json_output_keeps_traces_on_stderr() {
+    for format in ["json", "jsonl"] {
+        let output = run(&["6", "-i", "-", "-f", format, "-t", "step"], DAY6_EXAMPLE);
+        assert!(output.status.success(), "{:?}", output);
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+
+        assert!(!stdout.trim().is_empty());
+        for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+            let line = line.trim();
+            assert!(
+                line.starts_with(['{', '[', '}', ']', '"']),
+                "non-JSON line on stdout with --format {}: {}",
+                format,
+                line
+            );
+        }
+        assert!(!stdout.contains("[day 6 part"), "{}", stdout);
+        assert!(stderr.contains("[day 6 part 1]"), "{}", stderr);
+    }
+}
+
+#[test]
+fn text_output_keeps_traces_on_stdout() {
+    let output = run(&["6", "-i", "-", "-t", "summary"], DAY6_EXAMPLE);
+    assert!(output.status.success(), "{:?}", output);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[day 6 part 1]"), "{}", stdout);
+}